@@ -0,0 +1,51 @@
+//! Compares how long `Mesh::from_obj_file` and `FlatMesh::from_obj_file` take to read `body.obj`,
+//! both in strict mode. They share the streaming line parser, so the difference comes from what
+//! each builds: `Mesh` a `FaceElement` vector per face along with its groups and materials, and
+//! `FlatMesh` flat index buffers. Run with `cargo bench --bench obj_loading`.
+
+use std::fs;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tiny_renderer::mesh::{FlatMesh, Mesh, ParseMode};
+
+const BODY_OBJ: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/obj/body.obj");
+const RUNS: usize = 10;
+
+/// Runs `load` once to warm up and then `RUNS` times, printing and returning the best time.
+fn time(name: &str, mut load: impl FnMut()) -> Duration {
+    load();
+
+    let times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            load();
+            start.elapsed()
+        })
+        .collect();
+    let best = *times.iter().min().unwrap();
+    let mean = times.iter().sum::<Duration>() / RUNS as u32;
+
+    println!("{:<28} best {:>9.2?}  mean {:>9.2?}", name, best, mean);
+    best
+}
+
+fn main() {
+    let obj = fs::read_to_string(BODY_OBJ).unwrap();
+    println!("body.obj, {} lines, {} runs", obj.lines().count(), RUNS);
+
+    let mesh = time("Mesh::from_obj_file", || {
+        black_box(Mesh::from_obj_file(BODY_OBJ).unwrap());
+    });
+    let flat = time("FlatMesh::from_obj_file", || {
+        black_box(FlatMesh::from_obj_file(BODY_OBJ, ParseMode::Strict).unwrap());
+    });
+    time("FlatMesh::from_obj_str", || {
+        black_box(FlatMesh::from_obj_str(&obj, ParseMode::Strict).unwrap());
+    });
+
+    println!(
+        "Mesh::from_obj_file takes {:.2}x as long as FlatMesh::from_obj_file on the same parser",
+        mesh.as_secs_f64() / flat.as_secs_f64()
+    );
+}
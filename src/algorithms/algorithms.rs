@@ -1,21 +1,25 @@
-use crate::graphics::{PixelPos, RenderTarget, color};
+use crate::graphics::{DepthBuffer, RenderTarget, color};
+use crate::shader::{ScreenVertex, Shader, Uniforms};
+
+pub type RasterizeTriangleAlg<T> = fn(
+    &ScreenVertex,
+    &ScreenVertex,
+    &ScreenVertex,
+    &dyn Shader,
+    &Uniforms,
+    &mut DepthBuffer,
+    &mut T,
+);
 
 pub struct Algorithms<T: RenderTarget> {
     pub draw_line_alg: fn(i32, i32, i32, i32, &color::RGBA, &mut T),
-    pub rasterize_triangle_alg:
-        fn(&PixelPos, &PixelPos, &PixelPos, &color::RGBA, &mut T),
+    pub rasterize_triangle_alg: RasterizeTriangleAlg<T>,
 }
 
 impl<T: RenderTarget> Algorithms<T> {
     pub fn new(
         draw_line_alg: fn(i32, i32, i32, i32, &color::RGBA, &mut T),
-        rasterize_triangle_alg: fn(
-            &PixelPos,
-            &PixelPos,
-            &PixelPos,
-            &color::RGBA,
-            &mut T,
-        ),
+        rasterize_triangle_alg: RasterizeTriangleAlg<T>,
     ) -> Algorithms<T> {
         Algorithms {
             draw_line_alg,
@@ -23,3 +27,12 @@ impl<T: RenderTarget> Algorithms<T> {
         }
     }
 }
+
+// written out because deriving would require `T` itself to be `Copy`
+impl<T: RenderTarget> Clone for Algorithms<T> {
+    fn clone(&self) -> Algorithms<T> {
+        *self
+    }
+}
+
+impl<T: RenderTarget> Copy for Algorithms<T> {}
@@ -1,3 +1,4 @@
+use crate::geometry::Rect;
 use crate::graphics::{color, output::RenderTarget};
 
 pub fn bresenhams_line_alg<T>(
@@ -98,3 +99,131 @@ pub fn line_alg_with_floats<T>(
         }
     }
 }
+
+/// Xiaolin Wu's anti-aliased line. Each step along the major axis splits the color between the
+/// two pixels straddling the ideal line, weighted by how close each is, and blends it into the
+/// render output. The endpoints are whole pixels, so they get full coverage.
+pub fn xiaolin_wu_line_alg<T>(
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    color: &color::RGBA,
+    render_output: &mut T,
+) where
+    T: RenderTarget,
+{
+    let steep = (x1 - x0).abs() < (y1 - y0).abs();
+
+    // transpose it if it's steep
+    let (x0, y0, x1, y1) = if steep {
+        (y0, x0, y1, x1)
+    } else {
+        (x0, y0, x1, y1)
+    };
+
+    // if going right to left, we need to swap the points to go left to right
+    let (x0, y0, x1, y1) = if x0 > x1 {
+        (x1, y1, x0, y0)
+    } else {
+        (x0, y0, x1, y1)
+    };
+
+    let mut plot = |x: i32, y: i32, coverage: f32| {
+        if coverage <= 0.0 {
+            return;
+        }
+
+        let color = color.with_coverage(coverage);
+        if steep {
+            render_output.blend(y, x, &color);
+        } else {
+            render_output.blend(x, y, &color);
+        }
+    };
+
+    let dx = x1 - x0;
+    let gradient = if dx == 0 {
+        0.0
+    } else {
+        (y1 - y0) as f64 / dx as f64
+    };
+
+    plot(x0, y0, 1.0);
+    if dx == 0 {
+        return;
+    }
+
+    let mut intery = y0 as f64 + gradient;
+    for x in x0 + 1..x1 {
+        let y = intery.floor();
+        let fraction = (intery - y) as f32;
+
+        plot(x, y as i32, 1.0 - fraction);
+        plot(x, y as i32 + 1, fraction);
+
+        intery += gradient;
+    }
+
+    plot(x1, y1, 1.0);
+}
+
+/// Clips a segment to `rect`, returning the endpoints of the part inside it or `None` if nothing
+/// is left. Segments entirely inside come back untouched. Otherwise the parametric range is
+/// narrowed with Liang-Barsky and the new endpoints are rounded to the nearest pixel, so lines
+/// that cross the edge may start a pixel differently than if they had been drawn unclipped.
+pub fn clip_line(x0: i32, y0: i32, x1: i32, y1: i32, rect: &Rect) -> Option<(i32, i32, i32, i32)> {
+    if rect.is_empty() {
+        return None;
+    }
+
+    // Cohen-Sutherland outcodes settle the trivial cases without any division
+    let outcode = |x: i32, y: i32| {
+        ((x < rect.x_min) as u8)
+            | ((x > rect.x_max) as u8) << 1
+            | ((y < rect.y_min) as u8) << 2
+            | ((y > rect.y_max) as u8) << 3
+    };
+    let (code0, code1) = (outcode(x0, y0), outcode(x1, y1));
+    if code0 | code1 == 0 {
+        return Some((x0, y0, x1, y1));
+    }
+    if code0 & code1 != 0 {
+        return None;
+    }
+
+    let (fx0, fy0) = (x0 as f64, y0 as f64);
+    let (dx, dy) = (x1 as f64 - fx0, y1 as f64 - fy0);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+
+    // each edge as p * t <= q, one for each side of the rectangle
+    let edges = [
+        (-dx, fx0 - rect.x_min as f64),
+        (dx, rect.x_max as f64 - fx0),
+        (-dy, fy0 - rect.y_min as f64),
+        (dy, rect.y_max as f64 - fy0),
+    ];
+
+    for (p, q) in edges {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    // rounding can't leave the rectangle, since its bounds are whole pixels
+    let point = |t: f64| ((fx0 + dx * t).round() as i32, (fy0 + dy * t).round() as i32);
+    let (cx0, cy0) = point(t0);
+    let (cx1, cy1) = point(t1);
+
+    Some((cx0, cy0, cx1, cy1))
+}
@@ -1,10 +1,17 @@
+#[allow(clippy::module_inception)]
 pub mod algorithms;
 pub mod line_raster;
+pub mod thick_line;
 pub mod triangle_raster;
 
-pub use algorithms::Algorithms;
+pub use algorithms::{Algorithms, RasterizeTriangleAlg};
 pub use line_raster::bresenhams_line_alg;
+pub use line_raster::clip_line;
 pub use line_raster::line_alg_with_floats;
+pub use line_raster::xiaolin_wu_line_alg;
 
+pub use thick_line::{LineCap, LineJoin, LineStyle, draw_thick_polyline};
+
+pub use triangle_raster::rasterize_half_space;
 pub use triangle_raster::rasterize_triangle;
 pub use triangle_raster::rasterize_triangle_scanline;
@@ -0,0 +1,343 @@
+use crate::geometry::Rect;
+use crate::graphics::{color, output::RenderTarget};
+
+// miters longer than this many half widths turn into bevels, the same default SVG uses
+const MITER_LIMIT: f32 = 4.0;
+
+/// How the open ends of a thick line are finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    /// The line stops exactly at its endpoints.
+    Butt,
+    /// The line extends half its width past its endpoints.
+    Square,
+    /// A half disc is added at each endpoint.
+    Round,
+}
+
+/// How two segments of a thick polyline meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet, falling back to a bevel for sharp angles.
+    Miter,
+    /// The outer corners are connected with a straight edge.
+    Bevel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    /// Width in pixels. Widths of 1 or less use the renderer's line algorithm instead.
+    pub width: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+}
+
+impl Default for LineStyle {
+    fn default() -> LineStyle {
+        LineStyle {
+            width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+        }
+    }
+}
+
+impl LineStyle {
+    pub fn new(width: f32, cap: LineCap, join: LineJoin) -> LineStyle {
+        LineStyle { width, cap, join }
+    }
+
+    /// How far a stroke can reach past the points it goes through, which is furthest at the tip
+    /// of a miter join.
+    pub fn extent(&self) -> f32 {
+        self.width / 2.0 * MITER_LIMIT
+    }
+}
+
+/// Strokes a polyline through `points` in screen space, where pixel (x, y) covers [x, x + 1) by
+/// [y, y + 1). Closed polylines get a join at every point and no caps. Each piece of the stroke is
+/// filled as its own convex shape, clamped to `bounds`.
+pub fn draw_thick_polyline<T>(
+    points: &[(f32, f32)],
+    closed: bool,
+    style: &LineStyle,
+    color: &color::RGBA,
+    bounds: &Rect,
+    render_output: &mut T,
+) where
+    T: RenderTarget,
+{
+    let half_width = style.width / 2.0;
+    if points.is_empty() || half_width <= 0.0 {
+        return;
+    }
+
+    let segment_count = if closed && points.len() > 2 {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+
+    if segment_count == 0 {
+        // a lone point only shows up with caps that reach past it
+        draw_cap_at_point(
+            points[0],
+            half_width,
+            style.cap,
+            color,
+            bounds,
+            render_output,
+        );
+        return;
+    }
+
+    for i in 0..segment_count {
+        let a = points[i];
+        let b = points[(i + 1) % points.len()];
+        let is_first = !closed && i == 0;
+        let is_last = !closed && i == segment_count - 1;
+
+        let Some(direction) = normalized(b.0 - a.0, b.1 - a.1) else {
+            if is_first || is_last {
+                draw_cap_at_point(a, half_width, style.cap, color, bounds, render_output);
+            }
+            continue;
+        };
+        let normal = (-direction.1 * half_width, direction.0 * half_width);
+
+        // square caps push the ends of the body out, round caps add discs on top
+        let extend = |is_end: bool| {
+            if is_end && style.cap == LineCap::Square {
+                half_width
+            } else {
+                0.0
+            }
+        };
+        let start = (
+            a.0 - direction.0 * extend(is_first),
+            a.1 - direction.1 * extend(is_first),
+        );
+        let end = (
+            b.0 + direction.0 * extend(is_last),
+            b.1 + direction.1 * extend(is_last),
+        );
+
+        fill_convex_polygon(
+            &[
+                (start.0 + normal.0, start.1 + normal.1),
+                (start.0 - normal.0, start.1 - normal.1),
+                (end.0 - normal.0, end.1 - normal.1),
+                (end.0 + normal.0, end.1 + normal.1),
+            ],
+            color,
+            bounds,
+            render_output,
+        );
+
+        if style.cap == LineCap::Round {
+            if is_first {
+                fill_disc(a, half_width, color, bounds, render_output);
+            }
+            if is_last {
+                fill_disc(b, half_width, color, bounds, render_output);
+            }
+        }
+    }
+
+    // open polylines only join at their inner points
+    let join_range = if closed && points.len() > 2 {
+        0..points.len()
+    } else {
+        1..points.len() - 1
+    };
+    for i in join_range {
+        let previous = points[(i + points.len() - 1) % points.len()];
+        let next = points[(i + 1) % points.len()];
+        draw_join(
+            previous,
+            points[i],
+            next,
+            half_width,
+            style.join,
+            color,
+            bounds,
+            render_output,
+        );
+    }
+}
+
+/// Fills the wedge on the outside of the turn at `point`. The inside of the turn is already
+/// covered by the two segments.
+#[allow(clippy::too_many_arguments)]
+fn draw_join<T: RenderTarget>(
+    previous: (f32, f32),
+    point: (f32, f32),
+    next: (f32, f32),
+    half_width: f32,
+    join: LineJoin,
+    color: &color::RGBA,
+    bounds: &Rect,
+    render_output: &mut T,
+) {
+    let (Some(d0), Some(d1)) = (
+        normalized(point.0 - previous.0, point.1 - previous.1),
+        normalized(next.0 - point.0, next.1 - point.1),
+    ) else {
+        return;
+    };
+
+    let turn = d0.0 * d1.1 - d0.1 * d1.0;
+    if turn == 0.0 {
+        return;
+    }
+
+    // the left normal points outside on right turns and inside on left turns
+    let side = if turn > 0.0 { -half_width } else { half_width };
+    let corner0 = (point.0 - d0.1 * side, point.1 + d0.0 * side);
+    let corner1 = (point.0 - d1.1 * side, point.1 + d1.0 * side);
+
+    if join == LineJoin::Miter {
+        let bisector = normalized(
+            corner0.0 + corner1.0 - 2.0 * point.0,
+            corner0.1 + corner1.1 - 2.0 * point.1,
+        );
+
+        if let Some(bisector) = bisector {
+            // the cosine of half the angle between the segments' normals
+            let cos_half = (bisector.0 * (corner0.0 - point.0)
+                + bisector.1 * (corner0.1 - point.1))
+                / half_width;
+
+            if cos_half > 1.0 / MITER_LIMIT {
+                let length = half_width / cos_half;
+                let tip = (point.0 + bisector.0 * length, point.1 + bisector.1 * length);
+                fill_convex_polygon(
+                    &[point, corner0, tip, corner1],
+                    color,
+                    bounds,
+                    render_output,
+                );
+                return;
+            }
+        }
+    }
+
+    fill_convex_polygon(&[point, corner0, corner1], color, bounds, render_output);
+}
+
+fn draw_cap_at_point<T: RenderTarget>(
+    point: (f32, f32),
+    half_width: f32,
+    cap: LineCap,
+    color: &color::RGBA,
+    bounds: &Rect,
+    render_output: &mut T,
+) {
+    match cap {
+        LineCap::Butt => {}
+        LineCap::Square => fill_convex_polygon(
+            &[
+                (point.0 - half_width, point.1 - half_width),
+                (point.0 + half_width, point.1 - half_width),
+                (point.0 + half_width, point.1 + half_width),
+                (point.0 - half_width, point.1 + half_width),
+            ],
+            color,
+            bounds,
+            render_output,
+        ),
+        LineCap::Round => fill_disc(point, half_width, color, bounds, render_output),
+    }
+}
+
+fn normalized(x: f32, y: f32) -> Option<(f32, f32)> {
+    let length = (x * x + y * y).sqrt();
+    if length == 0.0 {
+        None
+    } else {
+        Some((x / length, y / length))
+    }
+}
+
+/// The pixels whose centers fall in the box around `points`, clamped to `bounds`.
+fn pixel_bounds(points: &[(f32, f32)], bounds: &Rect) -> Rect {
+    let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+    let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for &(x, y) in points {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    Rect::new(
+        (min_x - 0.5).ceil().max(bounds.x_min as f32) as i32,
+        (min_y - 0.5).ceil().max(bounds.y_min as f32) as i32,
+        (max_x - 0.5).floor().min(bounds.x_max as f32) as i32,
+        (max_y - 0.5).floor().min(bounds.y_max as f32) as i32,
+    )
+}
+
+/// Sets every pixel whose center is inside a convex polygon of either winding.
+fn fill_convex_polygon<T: RenderTarget>(
+    points: &[(f32, f32)],
+    color: &color::RGBA,
+    bounds: &Rect,
+    render_output: &mut T,
+) {
+    let area = signed_area(points);
+    if area == 0.0 {
+        return;
+    }
+    let sign = area.signum();
+
+    let rect = pixel_bounds(points, bounds);
+    for y in rect.y_min..=rect.y_max {
+        for x in rect.x_min..=rect.x_max {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+
+            let inside = (0..points.len()).all(|i| {
+                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                ((b.0 - a.0) * (py - a.1) - (b.1 - a.1) * (px - a.0)) * sign >= 0.0
+            });
+            if inside {
+                render_output.set(x, y, color);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of a polygon, positive when counter clockwise.
+fn signed_area(points: &[(f32, f32)]) -> f32 {
+    (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum()
+}
+
+fn fill_disc<T: RenderTarget>(
+    center: (f32, f32),
+    radius: f32,
+    color: &color::RGBA,
+    bounds: &Rect,
+    render_output: &mut T,
+) {
+    let rect = pixel_bounds(
+        &[
+            (center.0 - radius, center.1 - radius),
+            (center.0 + radius, center.1 + radius),
+        ],
+        bounds,
+    );
+
+    for y in rect.y_min..=rect.y_max {
+        for x in rect.x_min..=rect.x_max {
+            let (dx, dy) = (x as f32 + 0.5 - center.0, y as f32 + 0.5 - center.1);
+            if dx * dx + dy * dy <= radius * radius {
+                render_output.set(x, y, color);
+            }
+        }
+    }
+}
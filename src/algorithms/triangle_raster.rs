@@ -1,154 +1,402 @@
-use std::collections::HashMap;
-
-use crate::graphics::{PixelPos, RenderTarget, color};
+use crate::geometry::Rect;
+use crate::graphics::{DepthBuffer, RenderTarget};
+use crate::shader::{ScreenVertex, Shader, Uniforms, Varyings};
 
+/// Half-space rasterizer. Covered pixels are found with `rasterize_half_space` and shaded with
+/// perspective-correct varyings. Triangles reaching `MAX_COORDINATE` are skipped.
 pub fn rasterize_triangle<T>(
-    v0: &PixelPos,
-    v1: &PixelPos,
-    v2: &PixelPos,
-    color: &color::RGBA,
+    v0: &ScreenVertex,
+    v1: &ScreenVertex,
+    v2: &ScreenVertex,
+    shader: &dyn Shader,
+    uniforms: &Uniforms,
+    depth_buffer: &mut DepthBuffer,
     render_output: &mut T,
 ) where
     T: RenderTarget,
 {
-    // Sort vertices by y coordinate (v0 has smallest y, v2 has largest y)
-    let (v0, v1, v2) = if v1.y < v0.y {
-        (v1, v0, v2)
-    } else {
-        (v0, v1, v2)
-    };
+    let bounds = render_output.bounds();
 
-    let (v0, v1, v2) = if v2.y < v1.y {
-        (v0, v2, v1)
-    } else {
-        (v0, v1, v2)
+    rasterize_half_space(
+        (v0.x, v0.y),
+        (v1.x, v1.y),
+        (v2.x, v2.y),
+        &bounds,
+        |x, y, weights| {
+            shade_fragment(
+                x,
+                y,
+                [v0, v1, v2],
+                weights,
+                shader,
+                uniforms,
+                depth_buffer,
+                render_output,
+            )
+        },
+    );
+}
+
+/// Calls `visit` with every pixel inside `bounds` whose center is covered by the triangle, along
+/// with the screen space barycentric weights of `p0`, `p1` and `p2` at that center. Coverage
+/// follows the same top-left rule as `rasterize_triangle_scanline`, so the two agree pixel for
+/// pixel.
+///
+/// Vertices are snapped to 1/256 of a pixel and each edge function is stepped with integer adds.
+/// The bounding box is walked in 8x8 blocks, where a block whose corners are all outside one
+/// edge is skipped and a block whose corners are all inside every edge needs no per-pixel tests.
+///
+/// Nothing is visited if any vertex is `MAX_COORDINATE` pixels or more from the origin on either
+/// axis, because it can't be snapped exactly. Clip such triangles first, like the renderer does
+/// with its guard band.
+pub fn rasterize_half_space<F>(
+    p0: (f32, f32),
+    p1: (f32, f32),
+    p2: (f32, f32),
+    bounds: &Rect,
+    mut visit: F,
+) where
+    F: FnMut(i32, i32, (f32, f32, f32)),
+{
+    let (Some(a), Some(b), Some(c)) = (subpixel(p0), subpixel(p1), subpixel(p2)) else {
+        return;
     };
 
-    // Get image dimensions
-    let width = render_output.width() as i32;
-    let height = render_output.height() as i32;
+    // wind counter clockwise so the inside is on the positive side of every edge
+    let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    if area == 0 {
+        return;
+    }
+    let is_flipped = area < 0;
+    let (b, c) = if is_flipped { (c, b) } else { (b, c) };
+    let inv_area = 1.0 / area.abs() as f32;
+
+    // each edge's function is the weight of the vertex opposite it, scaled by the area
+    let edges = [
+        HalfSpaceEdge::new(b, c),
+        HalfSpaceEdge::new(c, a),
+        HalfSpaceEdge::new(a, b),
+    ];
+
+    let x_min = first_center_at_or_after(a.0.min(b.0).min(c.0)).max(bounds.x_min as i64);
+    let y_min = first_center_at_or_after(a.1.min(b.1).min(c.1)).max(bounds.y_min as i64);
+    let x_max = (first_center_at_or_after(a.0.max(b.0).max(c.0) + 1) - 1).min(bounds.x_max as i64);
+    let y_max = (first_center_at_or_after(a.1.max(b.1).max(c.1) + 1) - 1).min(bounds.y_max as i64);
+
+    let mut block_y = y_min;
+    while block_y <= y_max {
+        let block_y_end = (block_y + BLOCK_SIZE - 1).min(y_max);
+
+        let mut block_x = x_min;
+        'blocks: while block_x <= x_max {
+            let block_x_end = (block_x + BLOCK_SIZE - 1).min(x_max);
+            let next_block_x = block_x + BLOCK_SIZE;
+
+            let mut is_fully_covered = true;
+            for edge in &edges {
+                let corners_inside = [
+                    (block_x, block_y),
+                    (block_x_end, block_y),
+                    (block_x, block_y_end),
+                    (block_x_end, block_y_end),
+                ]
+                .iter()
+                .filter(|&&(x, y)| edge.covers(edge.at_pixel(x, y)))
+                .count();
+
+                if corners_inside == 0 {
+                    block_x = next_block_x;
+                    continue 'blocks;
+                }
+                if corners_inside < 4 {
+                    is_fully_covered = false;
+                }
+            }
+
+            let mut row = edges.map(|edge| edge.at_pixel(block_x, block_y));
+            for y in block_y..=block_y_end {
+                let mut values = row;
+
+                for x in block_x..=block_x_end {
+                    let is_covered = is_fully_covered
+                        || edges
+                            .iter()
+                            .zip(values)
+                            .all(|(edge, value)| edge.covers(value));
 
-    // Find bounding box of the triangle
-    let min_x = (v0.x.min(v1.x).min(v2.x)).max(0);
-    let max_x = (v0.x.max(v1.x).max(v2.x)).min(width - 1);
-    let min_y = (v0.y.min(v1.y).min(v2.y)).max(0);
-    let max_y = (v0.y.max(v1.y).max(v2.y)).min(height - 1);
+                    if is_covered {
+                        let w0 = values[0] as f32 * inv_area;
+                        let w1 = values[1] as f32 * inv_area;
+                        let w2 = 1.0 - w0 - w1;
+                        let weights = if is_flipped {
+                            (w0, w2, w1)
+                        } else {
+                            (w0, w1, w2)
+                        };
 
-    // Rasterize using barycentric coordinates
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let p = PixelPos { x, y };
+                        visit(x as i32, y as i32, weights);
+                    }
 
-            // Calculate barycentric coordinates
-            let (w0, w1, w2) = barycentric_coords(&p, v0, v1, v2);
+                    for (value, edge) in values.iter_mut().zip(&edges) {
+                        *value += edge.step_x;
+                    }
+                }
 
-            // Check if point is inside triangle (all barycentric coordinates >= 0)
-            if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
-                // Draw the pixel
-                render_output.set(x, y, color);
+                for (value, edge) in row.iter_mut().zip(&edges) {
+                    *value += edge.step_y;
+                }
             }
+
+            block_x = next_block_x;
         }
+
+        block_y += BLOCK_SIZE;
     }
 }
 
-fn barycentric_coords(
-    p: &PixelPos,
-    v0: &PixelPos,
-    v1: &PixelPos,
-    v2: &PixelPos,
-) -> (f32, f32, f32) {
-    let denom = ((v1.y - v2.y) * (v0.x - v2.x) + (v2.x - v1.x) * (v0.y - v2.y)) as f32;
+// the half-space rasterizer tests coverage for blocks of this many pixels square at a time
+const BLOCK_SIZE: i64 = 8;
+
+/// The edge function of a directed edge in subpixels, positive to its left.
+#[derive(Debug, Clone, Copy)]
+struct HalfSpaceEdge {
+    // the function's value at the center of pixel (0, 0)
+    origin: i64,
+    step_x: i64,
+    step_y: i64,
+    // a center exactly on the edge only counts for left and bottom edges
+    bias: i64,
+}
+
+impl HalfSpaceEdge {
+    fn new(from: (i64, i64), to: (i64, i64)) -> HalfSpaceEdge {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+
+        // with the inside on the left, left edges point down and bottom edges point right
+        let is_left_or_bottom = dy < 0 || (dy == 0 && dx > 0);
+
+        HalfSpaceEdge {
+            origin: dx * (SUBPIXEL_HALF - from.1) - dy * (SUBPIXEL_HALF - from.0),
+            step_x: -dy * SUBPIXEL_ONE,
+            step_y: dx * SUBPIXEL_ONE,
+            bias: if is_left_or_bottom { 0 } else { -1 },
+        }
+    }
+
+    fn at_pixel(&self, x: i64, y: i64) -> i64 {
+        self.origin + x * self.step_x + y * self.step_y
+    }
+
+    fn covers(&self, value: i64) -> bool {
+        value + self.bias >= 0
+    }
+}
+
+/// Runs the fragment stage for one covered pixel if it passes the depth test, writing the color
+/// and depth unless the shader discards it.
+#[allow(clippy::too_many_arguments)]
+fn shade_fragment<T: RenderTarget>(
+    x: i32,
+    y: i32,
+    vertices: [&ScreenVertex; 3],
+    (w0, w1, w2): (f32, f32, f32),
+    shader: &dyn Shader,
+    uniforms: &Uniforms,
+    depth_buffer: &mut DepthBuffer,
+    render_output: &mut T,
+) {
+    let [v0, v1, v2] = vertices;
+    let z = w0 * v0.z + w1 * v1.z + w2 * v2.z;
 
-    // Handle degenerate triangle
-    if denom.abs() < f32::EPSILON {
-        return (0.0, 0.0, 0.0);
+    // skip the shader entirely if something closer has been drawn there already
+    if !depth_buffer.test(x, y, z) {
+        return;
     }
 
-    let w0 = ((v1.y - v2.y) * (p.x - v2.x) + (v2.x - v1.x) * (p.y - v2.y)) as f32 / denom;
-    let w1 = ((v2.y - v0.y) * (p.x - v2.x) + (v0.x - v2.x) * (p.y - v2.y)) as f32 / denom;
-    let w2 = 1.0 - w0 - w1;
+    // screen space weights are skewed by the perspective divide, so weight each vertex by 1 / w
+    // to interpolate linearly in clip space instead
+    let (p0, p1, p2) = (w0 * v0.inv_w, w1 * v1.inv_w, w2 * v2.inv_w);
+    let sum = p0 + p1 + p2;
+    let (w0, w1, w2) = if sum.abs() > f32::EPSILON {
+        (p0 / sum, p1 / sum, p2 / sum)
+    } else {
+        (w0, w1, w2)
+    };
+
+    let varyings = Varyings::interpolate(&v0.varyings, &v1.varyings, &v2.varyings, w0, w1, w2);
 
-    (w0, w1, w2)
+    if let Some(color) = shader.fragment(&varyings, uniforms) {
+        depth_buffer.set(x, y, z);
+        render_output.set(x, y, &color);
+    }
 }
 
+/// Scanline rasterizer that walks the triangle's edges row by row. Vertices are snapped to a
+/// 1/256 pixel grid and the span ends are found with exact integer math, following the top-left
+/// fill rule: a pixel whose center lies exactly on an edge belongs to the triangle only if that
+/// edge is a left edge or a bottom edge (our y axis points up). Triangles sharing an edge
+/// therefore cover every pixel along it exactly once. Nothing is allocated per triangle.
+/// Triangles reaching `MAX_COORDINATE` are skipped, like in `rasterize_half_space`.
 pub fn rasterize_triangle_scanline<T>(
-    v0: &PixelPos,
-    v1: &PixelPos,
-    v2: &PixelPos,
-    color: &color::RGBA,
+    v0: &ScreenVertex,
+    v1: &ScreenVertex,
+    v2: &ScreenVertex,
+    shader: &dyn Shader,
+    uniforms: &Uniforms,
+    depth_buffer: &mut DepthBuffer,
     render_output: &mut T,
 ) where
     T: RenderTarget,
 {
-    let mut y_to_xs: HashMap<i32, Vec<i32>> = HashMap::new();
+    let (Some(p0), Some(p1), Some(p2)) = (
+        subpixel((v0.x, v0.y)),
+        subpixel((v1.x, v1.y)),
+        subpixel((v2.x, v2.y)),
+    ) else {
+        return;
+    };
 
-    bresenhams_line_map(v0.x, v0.y, v1.x, v1.y, &mut y_to_xs);
-    bresenhams_line_map(v1.x, v1.y, v2.x, v2.y, &mut y_to_xs);
-    bresenhams_line_map(v2.x, v2.y, v0.x, v0.y, &mut y_to_xs);
+    // sort by y, breaking ties by x so shared edges are always walked in the same direction
+    let mut sorted = [(p0, v0), (p1, v1), (p2, v2)];
+    sorted.sort_by_key(|&(p, _)| (p.1, p.0));
+    let [(p0, v0), (p1, v1), (p2, v2)] = sorted;
 
-    for (y, vec) in y_to_xs.iter() {
-        let mut min_x = i32::MAX;
-        let mut max_x = i32::MIN;
+    // which side of the long edge from p0 to p2 the middle vertex is on
+    let cross = (p2.0 - p0.0) * (p1.1 - p0.1) - (p2.1 - p0.1) * (p1.0 - p0.0);
+    if cross == 0 {
+        return;
+    }
+    let long_edge_is_left = cross < 0;
 
-        for &x in vec {
-            min_x = min_x.min(x);
-            max_x = max_x.max(x);
-        }
+    let bounds = render_output.bounds();
+    let (x_min, x_end) = (bounds.x_min as i64, bounds.x_max as i64 + 1);
+
+    // rows whose centers lie in [p0.y, p2.y), clamped to the render output
+    let first_row = first_center_at_or_after(p0.1).max(bounds.y_min as i64);
+    let end_row = first_center_at_or_after(p2.1).min(bounds.y_max as i64 + 1);
+    if first_row >= end_row {
+        return;
+    }
+    let middle_row = first_center_at_or_after(p1.1).clamp(first_row, end_row);
 
-        for x in min_x..max_x {
-            render_output.set(x, *y, color);
+    for (short_edge, rows) in [
+        ((p0, p1), first_row..middle_row),
+        ((p1, p2), middle_row..end_row),
+    ] {
+        let mut long_edge = EdgeWalker::new(p0, p2, rows.start);
+        let mut short_edge = EdgeWalker::new(short_edge.0, short_edge.1, rows.start);
+
+        for y in rows {
+            let (left, right) = if long_edge_is_left {
+                (long_edge.first_pixel(), short_edge.first_pixel())
+            } else {
+                (short_edge.first_pixel(), long_edge.first_pixel())
+            };
+
+            // the left edge's first pixel is in the span while the right edge's is not
+            for x in left.max(x_min)..right.min(x_end) {
+                let weights = barycentric_at(
+                    x as f32 + 0.5,
+                    y as f32 + 0.5,
+                    (v0.x, v0.y),
+                    (v1.x, v1.y),
+                    (v2.x, v2.y),
+                );
+                shade_fragment(
+                    x as i32,
+                    y as i32,
+                    [v0, v1, v2],
+                    weights,
+                    shader,
+                    uniforms,
+                    depth_buffer,
+                    render_output,
+                );
+            }
+
+            long_edge.step();
+            short_edge.step();
         }
     }
 }
 
-pub fn bresenhams_line_map(
-    x0: i32,
-    y0: i32,
-    x1: i32,
-    y1: i32,
-    y_to_xs: &mut HashMap<i32, Vec<i32>>,
-) {
-    let steep = (x1 - x0).abs() < (y1 - y0).abs();
+// vertices are snapped to 1 / 2^SUBPIXEL_BITS of a pixel
+const SUBPIXEL_BITS: u32 = 8;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
+const SUBPIXEL_HALF: i64 = SUBPIXEL_ONE / 2;
+/// How far from the origin, in pixels, a vertex may be for the rasterizers to draw its triangle.
+/// It keeps the edge products well within an i64, far beyond anything clipping lets through.
+pub const MAX_COORDINATE: f32 = (1 << 20) as f32;
 
-    // transpose it if it's steep
-    let (x0, y0, x1, y1) = if steep {
-        (y0, x0, y1, x1)
-    } else {
-        (x0, y0, x1, y1)
-    };
+/// Snaps a point to the subpixel grid, or `None` if it is too far out to rasterize exactly.
+fn subpixel((x, y): (f32, f32)) -> Option<(i64, i64)> {
+    if !(x.abs() < MAX_COORDINATE && y.abs() < MAX_COORDINATE) {
+        return None;
+    }
 
-    // if going right to left, we need to swap the points to go left to right
-    let (x0, y0, x1, y1) = if x0 > x1 {
-        (x1, y1, x0, y0)
-    } else {
-        (x0, y0, x1, y1)
-    };
+    let snap = |value: f32| (value * SUBPIXEL_ONE as f32).round() as i64;
+    Some((snap(x), snap(y)))
+}
 
-    let dx = x1 - x0;
-    let dy = y1 - y0;
-
-    let derror = (dy * 2).abs();
-    let mut error = 0;
-    let mut y = y0;
-
-    for x in x0..=x1 {
-        if steep {
-            y_to_xs
-                .entry(x)
-                .and_modify(|x: &mut Vec<i32>| x.push(y))
-                .or_insert(vec![y]);
-        } else {
-            y_to_xs
-                .entry(y)
-                .and_modify(|y: &mut Vec<i32>| y.push(x))
-                .or_insert(vec![x]);
-        }
+/// The first pixel row or column whose center is at or after a subpixel coordinate.
+fn first_center_at_or_after(coordinate: i64) -> i64 {
+    ceil_div(coordinate - SUBPIXEL_HALF, SUBPIXEL_ONE)
+}
 
-        error += derror;
+fn ceil_div(numerator: i64, denominator: i64) -> i64 {
+    -(-numerator).div_euclid(denominator)
+}
 
-        if error > dx {
-            y += if y1 > y0 { 1 } else { -1 };
-            error -= dx * 2;
+/// Walks an edge from its lower to its upper vertex one pixel row at a time, tracking where the
+/// edge crosses each row's center as an exact fraction.
+#[derive(Debug, Clone, Copy)]
+struct EdgeWalker {
+    // the first pixel whose center is at or right of the crossing is
+    // `ceil(numerator / (dy * SUBPIXEL_ONE))`
+    numerator: i64,
+    step: i64,
+    dy: i64,
+}
+
+impl EdgeWalker {
+    fn new(bottom: (i64, i64), top: (i64, i64), row: i64) -> EdgeWalker {
+        let dx = top.0 - bottom.0;
+        // horizontal edges cover no row centers, so they never get walked
+        let dy = (top.1 - bottom.1).max(1);
+        let row_center = row * SUBPIXEL_ONE + SUBPIXEL_HALF;
+
+        EdgeWalker {
+            numerator: (bottom.0 - SUBPIXEL_HALF) * dy + (row_center - bottom.1) * dx,
+            step: SUBPIXEL_ONE * dx,
+            dy,
         }
     }
+
+    fn first_pixel(&self) -> i64 {
+        ceil_div(self.numerator, self.dy * SUBPIXEL_ONE)
+    }
+
+    fn step(&mut self) {
+        self.numerator += self.step;
+    }
+}
+
+/// Barycentric weights of a point, as floats so the varyings can be interpolated at pixel
+/// centers rather than at snapped vertices.
+fn barycentric_at(
+    x: f32,
+    y: f32,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    (x2, y2): (f32, f32),
+) -> (f32, f32, f32) {
+    let denom = (y1 - y2) * (x0 - x2) + (x2 - x1) * (y0 - y2);
+    if denom == 0.0 {
+        return (1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0);
+    }
+
+    let w0 = ((y1 - y2) * (x - x2) + (x2 - x1) * (y - y2)) / denom;
+    let w1 = ((y2 - y0) * (x - x2) + (x0 - x2) * (y - y2)) / denom;
+
+    (w0, w1, 1.0 - w0 - w1)
 }
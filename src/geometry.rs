@@ -1,13 +1,13 @@
 use std::ops::{Add, Mul, Sub};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec3<T> {
     pub x: T,
     pub y: T,
     pub z: T,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec4<T> {
     pub x: T,
     pub y: T,
@@ -92,3 +92,446 @@ impl<T: Add<Output = T> + Mul<Output = T> + Copy> Mul for &Vec4<T> {
         (self.x * other.x) + (self.y * other.y) + (self.z * other.z) + (self.w * other.w)
     }
 }
+
+impl Vec3<f32> {
+    pub fn cross(&self, other: &Vec3<f32>) -> Vec3<f32> {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        (self * self).sqrt()
+    }
+
+    /// Returns the vector scaled to unit length, or the zero vector if it has no length.
+    pub fn normalized(&self) -> Vec3<f32> {
+        let length = self.length();
+        if length == 0.0 {
+            return Vec3::new(0.0, 0.0, 0.0);
+        }
+
+        Vec3 {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+        }
+    }
+
+    pub fn scaled(&self, factor: f32) -> Vec3<f32> {
+        Vec3 {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+}
+
+impl Vec4<f32> {
+    /// Divides x, y and z by w, taking a point from homogeneous clip space into normalized device
+    /// coordinates.
+    pub fn perspective_divide(&self) -> Vec3<f32> {
+        Vec3 {
+            x: self.x / self.w,
+            y: self.y / self.w,
+            z: self.z / self.w,
+        }
+    }
+}
+
+/// A row-major 3x3 matrix, mostly used for normal transforms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat3 {
+    pub m: [[f32; 3]; 3],
+}
+
+/// A row-major 4x4 matrix. Vectors are treated as columns, so `a * b * v` applies `b` first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f32; 4]; 4],
+}
+
+impl Mat3 {
+    pub fn identity() -> Mat3 {
+        Mat3 {
+            m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    /// The upper-left 3x3 block of `mat`, i.e. its rotation and scale without translation.
+    pub fn from_mat4(mat: &Mat4) -> Mat3 {
+        let mut m = [[0.0; 3]; 3];
+        for (row, values) in m.iter_mut().enumerate() {
+            values.copy_from_slice(&mat.m[row][..3]);
+        }
+        Mat3 { m }
+    }
+
+    pub fn transpose(&self) -> Mat3 {
+        let mut m = [[0.0; 3]; 3];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = self.m[col][row];
+            }
+        }
+        Mat3 { m }
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let m = &self.m;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    /// Returns `None` if the matrix is singular or the inverse doesn't fit in an `f32`. Tiny
+    /// determinants are fine, since a uniform scale of `s` alone gives one of `s³`.
+    pub fn inverse(&self) -> Option<Mat3> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let m = &self.m;
+        let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| {
+            m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+        };
+
+        // adjugate (transposed cofactor matrix) divided by the determinant
+        let adjugate = [
+            [
+                cofactor(1, 2, 1, 2),
+                -cofactor(0, 2, 1, 2),
+                cofactor(0, 1, 1, 2),
+            ],
+            [
+                -cofactor(1, 2, 0, 2),
+                cofactor(0, 2, 0, 2),
+                -cofactor(0, 1, 0, 2),
+            ],
+            [
+                cofactor(1, 2, 0, 1),
+                -cofactor(0, 2, 0, 1),
+                cofactor(0, 1, 0, 1),
+            ],
+        ];
+
+        let mut inverse = [[0.0; 3]; 3];
+        for row in 0..3 {
+            for col in 0..3 {
+                inverse[row][col] = adjugate[row][col] / det;
+            }
+        }
+        inverse
+            .iter()
+            .flatten()
+            .all(|value| value.is_finite())
+            .then_some(Mat3 { m: inverse })
+    }
+}
+
+impl Mul for &Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Self) -> Mat3 {
+        let mut m = [[0.0; 3]; 3];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.m[row][k] * other.m[k][col]).sum();
+            }
+        }
+        Mat3 { m }
+    }
+}
+
+impl Mul<&Vec3<f32>> for &Mat3 {
+    type Output = Vec3<f32>;
+
+    fn mul(self, v: &Vec3<f32>) -> Vec3<f32> {
+        let row = |r: usize| self.m[r][0] * v.x + self.m[r][1] * v.y + self.m[r][2] * v.z;
+        Vec3 {
+            x: row(0),
+            y: row(1),
+            z: row(2),
+        }
+    }
+}
+
+impl Mat4 {
+    pub fn identity() -> Mat4 {
+        Mat4 {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn translation(x: f32, y: f32, z: f32) -> Mat4 {
+        let mut mat = Mat4::identity();
+        mat.m[0][3] = x;
+        mat.m[1][3] = y;
+        mat.m[2][3] = z;
+        mat
+    }
+
+    pub fn scale(x: f32, y: f32, z: f32) -> Mat4 {
+        let mut mat = Mat4::identity();
+        mat.m[0][0] = x;
+        mat.m[1][1] = y;
+        mat.m[2][2] = z;
+        mat
+    }
+
+    /// Rotation of `angle` radians counter-clockwise around `axis` (Rodrigues' formula).
+    pub fn rotation(axis: &Vec3<f32>, angle: f32) -> Mat4 {
+        let Vec3 { x, y, z } = axis.normalized();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        Mat4 {
+            m: [
+                [
+                    t * x * x + cos,
+                    t * x * y - sin * z,
+                    t * x * z + sin * y,
+                    0.0,
+                ],
+                [
+                    t * x * y + sin * z,
+                    t * y * y + cos,
+                    t * y * z - sin * x,
+                    0.0,
+                ],
+                [
+                    t * x * z - sin * y,
+                    t * y * z + sin * x,
+                    t * z * z + cos,
+                    0.0,
+                ],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn rotation_x(angle: f32) -> Mat4 {
+        Mat4::rotation(&Vec3::new(1.0, 0.0, 0.0), angle)
+    }
+
+    pub fn rotation_y(angle: f32) -> Mat4 {
+        Mat4::rotation(&Vec3::new(0.0, 1.0, 0.0), angle)
+    }
+
+    pub fn rotation_z(angle: f32) -> Mat4 {
+        Mat4::rotation(&Vec3::new(0.0, 0.0, 1.0), angle)
+    }
+
+    /// Euler rotation in radians, applied around x first, then y, then z.
+    pub fn rotation_euler(x: f32, y: f32, z: f32) -> Mat4 {
+        &(&Mat4::rotation_z(z) * &Mat4::rotation_y(y)) * &Mat4::rotation_x(x)
+    }
+
+    /// A right-handed view matrix for a camera at `eye` looking at `target`.
+    pub fn look_at(eye: &Vec3<f32>, target: &Vec3<f32>, up: &Vec3<f32>) -> Mat4 {
+        let forward = (target - eye).normalized();
+        let right = forward.cross(up).normalized();
+        let up = right.cross(&forward);
+
+        Mat4 {
+            m: [
+                [right.x, right.y, right.z, -(&right * eye)],
+                [up.x, up.y, up.z, -(&up * eye)],
+                [-forward.x, -forward.y, -forward.z, &forward * eye],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    /// An OpenGL style perspective projection, mapping the view frustum to the [-1, 1] cube.
+    /// `fov_y` is the vertical field of view in radians.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Mat4 {
+        let f = 1.0 / (fov_y / 2.0).tan();
+
+        Mat4 {
+            m: [
+                [f / aspect, 0.0, 0.0, 0.0],
+                [0.0, f, 0.0, 0.0],
+                [
+                    0.0,
+                    0.0,
+                    (far + near) / (near - far),
+                    (2.0 * far * near) / (near - far),
+                ],
+                [0.0, 0.0, -1.0, 0.0],
+            ],
+        }
+    }
+
+    /// An OpenGL style orthographic projection, mapping the given box to the [-1, 1] cube.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Mat4 {
+        Mat4 {
+            m: [
+                [
+                    2.0 / (right - left),
+                    0.0,
+                    0.0,
+                    -(right + left) / (right - left),
+                ],
+                [
+                    0.0,
+                    2.0 / (top - bottom),
+                    0.0,
+                    -(top + bottom) / (top - bottom),
+                ],
+                [0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near)],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    /// Maps normalized device coordinates to pixel coordinates of a `width` x `height` target
+    /// whose lower left corner is at (`x`, `y`). Depth goes from 1 at the near plane to 0 at the
+    /// far plane, so larger depth values are closer like in `DepthBuffer`.
+    pub fn viewport(x: f32, y: f32, width: f32, height: f32) -> Mat4 {
+        Mat4 {
+            m: [
+                [width / 2.0, 0.0, 0.0, x + width / 2.0],
+                [0.0, height / 2.0, 0.0, y + height / 2.0],
+                [0.0, 0.0, -0.5, 0.5],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        }
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = self.m[col][row];
+            }
+        }
+        Mat4 { m }
+    }
+
+    /// Inverts the matrix with Gauss-Jordan elimination. Returns `None` if it is singular or the
+    /// inverse doesn't fit in an `f32`.
+    pub fn inverse(&self) -> Option<Mat4> {
+        let mut a = self.m;
+        let mut inverse = Mat4::identity().m;
+
+        for col in 0..4 {
+            // partial pivoting: use the row with the largest value in this column
+            let pivot = (col..4)
+                .max_by(|&r0, &r1| a[r0][col].abs().total_cmp(&a[r1][col].abs()))
+                .unwrap_or(col);
+
+            if a[pivot][col] == 0.0 || !a[pivot][col].is_finite() {
+                return None;
+            }
+
+            a.swap(col, pivot);
+            inverse.swap(col, pivot);
+
+            let scale = 1.0 / a[col][col];
+            for k in 0..4 {
+                a[col][k] *= scale;
+                inverse[col][k] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == col {
+                    continue;
+                }
+
+                let factor = a[row][col];
+                for k in 0..4 {
+                    a[row][k] -= factor * a[col][k];
+                    inverse[row][k] -= factor * inverse[col][k];
+                }
+            }
+        }
+
+        inverse
+            .iter()
+            .flatten()
+            .all(|value| value.is_finite())
+            .then_some(Mat4 { m: inverse })
+    }
+}
+
+impl Mul for &Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Self) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (row, values) in m.iter_mut().enumerate() {
+            for (col, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[row][k] * other.m[k][col]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
+
+impl Mul<&Vec4<f32>> for &Mat4 {
+    type Output = Vec4<f32>;
+
+    fn mul(self, v: &Vec4<f32>) -> Vec4<f32> {
+        let row = |r: usize| {
+            self.m[r][0] * v.x + self.m[r][1] * v.y + self.m[r][2] * v.z + self.m[r][3] * v.w
+        };
+        Vec4 {
+            x: row(0),
+            y: row(1),
+            z: row(2),
+            w: row(3),
+        }
+    }
+}
+
+/// An axis aligned rectangle of pixels. Both corners are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x_min: i32,
+    pub y_min: i32,
+    pub x_max: i32,
+    pub y_max: i32,
+}
+
+impl Rect {
+    pub fn new(x_min: i32, y_min: i32, x_max: i32, y_max: i32) -> Rect {
+        Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        }
+    }
+
+    /// The rectangle covering a `width` by `height` target, starting at (0, 0).
+    pub fn from_size(width: usize, height: usize) -> Rect {
+        Rect::new(0, 0, width as i32 - 1, height as i32 - 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x_min > self.x_max || self.y_min > self.y_max
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x_min && x <= self.x_max && y >= self.y_min && y <= self.y_max
+    }
+
+    /// The overlap of two rectangles, which may be empty.
+    pub fn intersect(&self, other: &Rect) -> Rect {
+        Rect::new(
+            self.x_min.max(other.x_min),
+            self.y_min.max(other.y_min),
+            self.x_max.min(other.x_max),
+            self.y_max.min(other.y_max),
+        )
+    }
+}
@@ -1,4 +1,5 @@
 #[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct RGBA {
     pub r: u8,
     pub g: u8,
@@ -30,6 +31,14 @@ pub const BLUE: RGBA = RGBA {
     a: 255,
 };
 
+#[allow(dead_code)]
+pub const WHITE: RGBA = RGBA {
+    r: 255,
+    g: 255,
+    b: 255,
+    a: 255,
+};
+
 #[allow(dead_code)]
 pub const PURPLE: RGBA = RGBA {
     r: 174,
@@ -37,3 +46,46 @@ pub const PURPLE: RGBA = RGBA {
     b: 220,
     a: 255,
 };
+
+impl RGBA {
+    /// Perceived brightness using the Rec. 601 weights, for writing grayscale images.
+    pub fn luminance(&self) -> u8 {
+        (0.299 * self.r as f32 + 0.587 * self.g as f32 + 0.114 * self.b as f32).round() as u8
+    }
+
+    /// The same color with its alpha scaled by `coverage`, clamped to [0, 1].
+    pub fn with_coverage(&self, coverage: f32) -> RGBA {
+        RGBA {
+            a: (self.a as f32 * coverage.clamp(0.0, 1.0)).round() as u8,
+            ..*self
+        }
+    }
+
+    /// Composites this color over `dst` with the "source over" operator, treating alpha as
+    /// straight (not premultiplied).
+    pub fn over(&self, dst: &RGBA) -> RGBA {
+        let src_a = self.a as f32 / 255.0;
+        let dst_a = dst.a as f32 / 255.0;
+        let out_a = src_a + dst_a * (1.0 - src_a);
+
+        if out_a == 0.0 {
+            return RGBA {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            };
+        }
+
+        let channel = |src: u8, dst: u8| {
+            ((src as f32 * src_a + dst as f32 * dst_a * (1.0 - src_a)) / out_a).round() as u8
+        };
+
+        RGBA {
+            r: channel(self.r, dst.r),
+            g: channel(self.g, dst.g),
+            b: channel(self.b, dst.b),
+            a: (out_a * 255.0).round() as u8,
+        }
+    }
+}
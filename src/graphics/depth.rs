@@ -0,0 +1,137 @@
+use crate::geometry::Rect;
+use crate::graphics::{RenderTarget, color};
+
+/// Per-pixel depth values for a render target. Larger values are closer to the viewer, and
+/// cleared pixels hold `f32::NEG_INFINITY` so that any fragment passes the first test.
+#[derive(Debug, Clone)]
+pub struct DepthBuffer {
+    width: usize,
+    height: usize,
+    // the pixels that are stored, all of them unless this is a tile of a larger buffer
+    region: Rect,
+    data: Vec<f32>,
+}
+
+impl DepthBuffer {
+    pub fn new(width: usize, height: usize) -> DepthBuffer {
+        DepthBuffer {
+            width,
+            height,
+            region: Rect::from_size(width, height),
+            data: vec![f32::NEG_INFINITY; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(f32::NEG_INFINITY);
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<f32> {
+        self.index(x, y).map(|i| self.data[i])
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    /// Whether `z` is closer than the depth stored at the given pixel. Out of bounds pixels never
+    /// pass.
+    pub fn test(&self, x: i32, y: i32, z: f32) -> bool {
+        self.get(x, y).is_some_and(|depth| z > depth)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, z: f32) {
+        if let Some(i) = self.index(x, y) {
+            self.data[i] = z;
+        }
+    }
+
+    /// Stores `z` at the given pixel if it is closer than what is already there. Returns whether
+    /// the fragment passed the depth test.
+    pub fn test_and_set(&mut self, x: i32, y: i32, z: f32) -> bool {
+        match self.index(x, y) {
+            Some(i) if z > self.data[i] => {
+                self.data[i] = z;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Writes the buffer into `render_output` as a grayscale image, mapping depth 1 (the near
+    /// plane) to white and depth 0 (the far plane) to black. Pixels that were never written stay
+    /// black.
+    pub fn draw_grayscale<T: RenderTarget>(&self, render_output: &mut T) {
+        for y in self.region.y_min..=self.region.y_max {
+            for x in self.region.x_min..=self.region.x_max {
+                let z = self.data[self.index(x, y).unwrap()];
+                let value = if z.is_finite() {
+                    (z.clamp(0.0, 1.0) * 255.0).round() as u8
+                } else {
+                    0
+                };
+
+                let gray = color::RGBA {
+                    r: value,
+                    g: value,
+                    b: value,
+                    a: value,
+                };
+                render_output.set(x, y, &gray);
+            }
+        }
+    }
+
+    /// A copy of the depths inside `region`, addressed with the same coordinates as the full
+    /// buffer. Pixels outside the region never pass the depth test.
+    pub(crate) fn tile(&self, region: &Rect) -> DepthBuffer {
+        let region = self.region.intersect(region);
+        let mut tile = DepthBuffer {
+            width: self.width,
+            height: self.height,
+            region,
+            data: Vec::new(),
+        };
+
+        if !region.is_empty() {
+            for y in region.y_min..=region.y_max {
+                let start = self.index(region.x_min, y).unwrap();
+                let end = self.index(region.x_max, y).unwrap() + 1;
+                tile.data.extend_from_slice(&self.data[start..end]);
+            }
+        }
+
+        tile
+    }
+
+    /// Writes a tile made with `tile` back into the buffer.
+    pub(crate) fn copy_from_tile(&mut self, tile: &DepthBuffer) {
+        if tile.region.is_empty() {
+            return;
+        }
+
+        for y in tile.region.y_min..=tile.region.y_max {
+            let source = tile.index(tile.region.x_min, y).unwrap();
+            let start = self.index(tile.region.x_min, y).unwrap();
+            let end = self.index(tile.region.x_max, y).unwrap() + 1;
+            self.data[start..end].copy_from_slice(&tile.data[source..source + end - start]);
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.region.contains(x, y) {
+            return None;
+        }
+
+        let stride = (self.region.x_max - self.region.x_min + 1) as usize;
+        Some((x - self.region.x_min) as usize + (y - self.region.y_min) as usize * stride)
+    }
+}
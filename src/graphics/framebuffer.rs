@@ -0,0 +1,141 @@
+use crate::geometry::Rect;
+use crate::graphics::{RenderTarget, color};
+
+/// An in-memory RGBA render target. Nothing is written anywhere on `render`, which makes it handy
+/// for tests and for rendering once and then saving in several formats with `copy_to`.
+#[derive(Debug, Clone)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    // the pixels that are stored, all of them unless this is a tile of a larger framebuffer
+    region: Rect,
+    pixels: Vec<color::RGBA>,
+}
+
+impl Framebuffer {
+    /// A framebuffer cleared to transparent black.
+    pub fn new(width: usize, height: usize) -> Framebuffer {
+        Framebuffer {
+            width,
+            height,
+            region: Rect::from_size(width, height),
+            pixels: vec![
+                color::RGBA {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 0,
+                };
+                width * height
+            ],
+        }
+    }
+
+    pub fn clear(&mut self, color: &color::RGBA) {
+        self.pixels.fill(*color);
+    }
+
+    /// The color of a pixel, with (0, 0) in the bottom left corner.
+    pub fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    /// All pixels, bottom row first.
+    pub fn pixels(&self) -> &[color::RGBA] {
+        &self.pixels
+    }
+
+    /// The pixels of row `y`, counted from the bottom. Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &[color::RGBA] {
+        let range = self.row_range(y);
+        &self.pixels[range]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [color::RGBA] {
+        let range = self.row_range(y);
+        &mut self.pixels[range]
+    }
+
+    /// Writes every pixel into `target`, for example a `tga::Image` or `png::Image`, which can then
+    /// be saved with its `render`. Pixels outside the target are dropped.
+    pub fn copy_to<T: RenderTarget + ?Sized>(&self, target: &mut T) {
+        for y in self.region.y_min..=self.region.y_max {
+            for (x, color) in (self.region.x_min..).zip(self.row(y as usize)) {
+                target.set(x, y, color);
+            }
+        }
+    }
+
+    /// A copy of the pixels inside `region`, which still reports the full width and height so
+    /// it can be drawn into with the same coordinates. Writes outside the region are dropped.
+    pub(crate) fn tile(&self, region: &Rect) -> Framebuffer {
+        let region = self.region.intersect(region);
+        let mut tile = Framebuffer {
+            width: self.width,
+            height: self.height,
+            region,
+            pixels: Vec::new(),
+        };
+
+        if !region.is_empty() {
+            for y in region.y_min..=region.y_max {
+                let row = self.row(y as usize);
+                let start = (region.x_min - self.region.x_min) as usize;
+                let end = (region.x_max - self.region.x_min) as usize + 1;
+                tile.pixels.extend_from_slice(&row[start..end]);
+            }
+        }
+
+        tile
+    }
+
+    fn row_range(&self, y: usize) -> std::ops::Range<usize> {
+        let y = y as i32;
+        assert!(
+            y >= self.region.y_min && y <= self.region.y_max,
+            "Row {} is outside the framebuffer",
+            y
+        );
+
+        let stride = (self.region.x_max - self.region.x_min + 1) as usize;
+        let start = (y - self.region.y_min) as usize * stride;
+        start..start + stride
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.region.contains(x, y) {
+            return None;
+        }
+
+        let stride = (self.region.x_max - self.region.x_min + 1) as usize;
+        Some((x - self.region.x_min) as usize + (y - self.region.y_min) as usize * stride)
+    }
+}
+
+impl RenderTarget for Framebuffer {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = *color;
+        }
+    }
+
+    fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        Framebuffer::get(self, x, y)
+    }
+
+    fn bounds(&self) -> Rect {
+        self.region
+    }
+}
@@ -1,8 +1,15 @@
 pub mod color;
+pub mod depth;
+pub mod framebuffer;
 pub mod output;
+pub mod png;
+pub mod pnm;
+pub mod texture;
 pub mod tga;
 pub mod window;
 
-pub use output::PixelPos;
-pub use output::RenderTarget;
+pub use depth::DepthBuffer;
+pub use framebuffer::Framebuffer;
+pub use output::{RenderTarget, image_for_path};
+pub use texture::{Filter, Texture, TextureError, WrapMode};
 pub use window::TinyRendererWindow;
@@ -1,16 +1,110 @@
 use std::error::Error;
+use std::path::Path;
 
-use crate::graphics::color;
-
-#[derive(Debug)]
-pub struct PixelPos {
-    pub x: i32,
-    pub y: i32,
-}
+use crate::geometry::Rect;
+use crate::graphics::{color, png, pnm, tga};
 
 pub trait RenderTarget {
     fn width(&self) -> usize;
     fn height(&self) -> usize;
     fn set(&mut self, x: i32, y: i32, color: &color::RGBA);
     fn render(&mut self) -> Result<(), Box<dyn Error>>;
+
+    /// Reads a pixel back, with (0, 0) in the bottom left corner. Targets that don't keep their
+    /// pixels around return `None`, as does any pixel out of bounds.
+    fn get(&self, _x: i32, _y: i32) -> Option<color::RGBA> {
+        None
+    }
+
+    /// Draws `color` over the existing pixel, using its alpha as opacity. Targets without
+    /// read-back blend against opaque black, which is what a fresh image holds.
+    fn blend(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        let dst = self.get(x, y).unwrap_or(color::RGBA {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 255,
+        });
+        self.set(x, y, &color.over(&dst));
+    }
+
+    /// The pixels this target holds. Everything in `width` by `height` unless the target is a
+    /// tile of a larger image, in which case rasterizers skip the pixels outside it.
+    fn bounds(&self) -> Rect {
+        Rect::from_size(self.width(), self.height())
+    }
+}
+
+impl<R: RenderTarget + ?Sized> RenderTarget for Box<R> {
+    fn width(&self) -> usize {
+        (**self).width()
+    }
+
+    fn height(&self) -> usize {
+        (**self).height()
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        (**self).set(x, y, color)
+    }
+
+    fn render(&mut self) -> Result<(), Box<dyn Error>> {
+        (**self).render()
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        (**self).get(x, y)
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        (**self).blend(x, y, color)
+    }
+
+    fn bounds(&self) -> Rect {
+        (**self).bounds()
+    }
+}
+
+/// Creates an RGB image target whose encoder is picked from the extension of `output_path`:
+/// `.tga`, `.png`, `.ppm` or `.pgm`.
+pub fn image_for_path(
+    output_path: &str,
+    width: u16,
+    height: u16,
+) -> Result<Box<dyn RenderTarget>, Box<dyn Error>> {
+    let extension = Path::new(output_path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+
+    let image: Box<dyn RenderTarget> = match extension.as_deref() {
+        Some("tga") => Box::new(tga::Image::new(
+            output_path,
+            width,
+            height,
+            tga::ImageType::UncompressedTrueColor,
+            tga::ColorType::RGB,
+        )),
+        Some("png") => Box::new(png::Image::new(
+            output_path,
+            width as u32,
+            height as u32,
+            png::ColorType::RGB,
+        )),
+        Some("ppm") => Box::new(pnm::Image::new(
+            output_path,
+            width as usize,
+            height as usize,
+            pnm::Format::Ppm,
+        )),
+        Some("pgm") => Box::new(pnm::Image::new(
+            output_path,
+            width as usize,
+            height as usize,
+            pnm::Format::Pgm,
+        )),
+        _ => return Err(format!("Unsupported image format: {}", output_path).into()),
+    };
+
+    Ok(image)
 }
@@ -0,0 +1,475 @@
+use std::{fs::File, io::Write, path::Path};
+
+use crate::graphics::{RenderTarget, color};
+
+pub use crate::graphics::tga::ColorType;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// deflate's sliding window and match limits
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Stored deflate blocks. Fast, but the file is as large as the raw pixels.
+    Stored,
+    /// LZ77 with fixed Huffman codes.
+    Deflate,
+}
+
+/// A PNG image render target, written to `output_path` on `render`.
+#[derive(Debug)]
+pub struct Image {
+    output_path: String,
+    width: u32,
+    height: u32,
+    color_type: ColorType,
+    compression: Compression,
+    data: Vec<u8>,
+}
+
+impl RenderTarget for Image {
+    fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return;
+        }
+
+        let bpp = self.bytes_per_pixel();
+        let start = (x as usize + y as usize * self.width as usize) * bpp;
+
+        match self.color_type {
+            ColorType::GrayScale => self.data[start] = color.luminance(),
+            ColorType::RGB => {
+                self.data[start..start + 3].copy_from_slice(&[color.r, color.g, color.b])
+            }
+            ColorType::RGBA => {
+                self.data[start..start + 4].copy_from_slice(&[color.r, color.g, color.b, color.a])
+            }
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return None;
+        }
+
+        let bpp = self.bytes_per_pixel();
+        let start = (x as usize + y as usize * self.width as usize) * bpp;
+        let pixel = &self.data[start..start + bpp];
+
+        Some(match self.color_type {
+            ColorType::GrayScale => color::RGBA {
+                r: pixel[0],
+                g: pixel[0],
+                b: pixel[0],
+                a: 255,
+            },
+            ColorType::RGB => color::RGBA {
+                r: pixel[0],
+                g: pixel[1],
+                b: pixel[2],
+                a: 255,
+            },
+            ColorType::RGBA => color::RGBA {
+                r: pixel[0],
+                g: pixel[1],
+                b: pixel[2],
+                a: pixel[3],
+            },
+        })
+    }
+
+    fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_to_file(self.output_path.as_str())?;
+        Ok(())
+    }
+}
+
+impl Image {
+    pub fn new(output_path: &str, width: u32, height: u32, color_type: ColorType) -> Image {
+        let data_length = width as usize * height as usize * color_type.bytes_per_pixel() as usize;
+
+        Image {
+            output_path: output_path.to_string(),
+            width,
+            height,
+            color_type,
+            compression: Compression::Deflate,
+            data: vec![0; data_length],
+        }
+    }
+
+    pub fn with_compression(mut self, compression: Compression) -> Image {
+        self.compression = compression;
+        self
+    }
+
+    pub fn write_to_file(&self, name: &str) -> std::io::Result<()> {
+        let mut file = File::create(Path::new(name))?;
+        file.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// The complete PNG file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let png_color_type = match self.color_type {
+            ColorType::GrayScale => 0,
+            ColorType::RGB => 2,
+            ColorType::RGBA => 6,
+        };
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bit depth, deflate compression, adaptive filtering, no interlacing
+        header.extend_from_slice(&[8, png_color_type, 0, 0, 0]);
+
+        let mut bytes = SIGNATURE.to_vec();
+        write_chunk(&mut bytes, b"IHDR", &header);
+        write_chunk(
+            &mut bytes,
+            b"IDAT",
+            &zlib_compress(&self.filtered_rows(), self.compression),
+        );
+        write_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.color_type.bytes_per_pixel() as usize
+    }
+
+    /// Each row prefixed with its filter type, top row first since our y axis points up.
+    fn filtered_rows(&self) -> Vec<u8> {
+        let bpp = self.bytes_per_pixel();
+        let row_length = self.width as usize * bpp;
+        let mut filtered = Vec::with_capacity((row_length + 1) * self.height as usize);
+
+        let zero_row = vec![0; row_length];
+        let mut previous: &[u8] = &zero_row;
+
+        for row in self.data.chunks_exact(row_length.max(1)).rev() {
+            if row_length == 0 {
+                filtered.push(0);
+                continue;
+            }
+
+            // pick the filter with the smallest sum of absolute values, the usual heuristic
+            let candidates: Vec<Vec<u8>> =
+                (0..5).map(|f| filter_row(f, row, previous, bpp)).collect();
+            let best = (0..5)
+                .min_by_key(|&f| {
+                    candidates[f]
+                        .iter()
+                        .map(|&b| (b as i8).unsigned_abs() as u64)
+                        .sum::<u64>()
+                })
+                .unwrap_or(0);
+
+            filtered.push(best as u8);
+            filtered.extend_from_slice(&candidates[best]);
+            previous = row;
+        }
+
+        filtered
+    }
+}
+
+/// Applies PNG filter `filter` (None, Sub, Up, Average, Paeth) to a row.
+fn filter_row(filter: usize, row: &[u8], previous: &[u8], bpp: usize) -> Vec<u8> {
+    (0..row.len())
+        .map(|i| {
+            let left = if i >= bpp { row[i - bpp] } else { 0 };
+            let up = previous[i];
+            let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+
+            let predictor = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                _ => paeth(left, up, up_left),
+            };
+            row[i].wrapping_sub(predictor)
+        })
+        .collect()
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = out.len();
+    out.extend_from_slice(chunk_type);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// The CRC-32 of every byte value, for `crc32`.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            c = if c & 1 != 0 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            bit += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+};
+
+/// CRC-32 as used by PNG chunks (and zip and gzip).
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffffffff;
+    for &byte in data {
+        crc = CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xffffffff
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before b could overflow
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// Wraps a deflate stream in a zlib header and Adler-32 trailer.
+pub fn zlib_compress(data: &[u8], compression: Compression) -> Vec<u8> {
+    let mut out = match compression {
+        Compression::Stored => vec![0x78, 0x01],
+        Compression::Deflate => vec![0x78, 0x9c],
+    };
+
+    match compression {
+        Compression::Stored => deflate_stored(data, &mut out),
+        Compression::Deflate => deflate_fixed(data, &mut out),
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn deflate_stored(data: &[u8], out: &mut Vec<u8>) {
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+
+    if blocks.peek().is_none() {
+        // an empty stream still needs one final block
+        out.extend_from_slice(&[0x01, 0x00, 0x00, 0xff, 0xff]);
+        return;
+    }
+
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let length = block.len() as u16;
+
+        out.push(is_final as u8);
+        out.extend_from_slice(&length.to_le_bytes());
+        out.extend_from_slice(&(!length).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+}
+
+/// Writes bits least significant bit first, as deflate expects.
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter<'_> {
+    fn write_bits(&mut self, value: u32, bits: u32) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += bits;
+
+        while self.count >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most significant bit first, so they go in reversed.
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.out.push(self.buffer as u8);
+            self.buffer = 0;
+            self.count = 0;
+        }
+    }
+}
+
+// (code, base length, extra bits) for lengths 3..=258
+#[rustfmt::skip]
+const LENGTH_CODES: [(u32, usize, u32); 29] = [
+    (257, 3, 0), (258, 4, 0), (259, 5, 0), (260, 6, 0), (261, 7, 0), (262, 8, 0),
+    (263, 9, 0), (264, 10, 0), (265, 11, 1), (266, 13, 1), (267, 15, 1), (268, 17, 1),
+    (269, 19, 2), (270, 23, 2), (271, 27, 2), (272, 31, 2), (273, 35, 3), (274, 43, 3),
+    (275, 51, 3), (276, 59, 3), (277, 67, 4), (278, 83, 4), (279, 99, 4), (280, 115, 4),
+    (281, 131, 5), (282, 163, 5), (283, 195, 5), (284, 227, 5), (285, 258, 0),
+];
+
+// (base distance, extra bits) for distance codes 0..=29
+#[rustfmt::skip]
+const DISTANCE_CODES: [(usize, u32); 30] = [
+    (1, 0), (2, 0), (3, 0), (4, 0), (5, 1), (7, 1), (9, 2), (13, 2), (17, 3), (25, 3),
+    (33, 4), (49, 4), (65, 5), (97, 5), (129, 6), (193, 6), (257, 7), (385, 7), (513, 8),
+    (769, 8), (1025, 9), (1537, 9), (2049, 10), (3073, 10), (4097, 11), (6145, 11),
+    (8193, 12), (12289, 12), (16385, 13), (24577, 13),
+];
+
+fn write_literal(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let (code, base, extra) = *LENGTH_CODES
+        .iter()
+        .rev()
+        .find(|(_, base, _)| *base <= length)
+        .unwrap_or(&LENGTH_CODES[0]);
+    write_literal(writer, code);
+    writer.write_bits((length - base) as u32, extra);
+
+    let index = DISTANCE_CODES
+        .iter()
+        .rposition(|(base, _)| *base <= distance)
+        .unwrap_or(0);
+    let (base, extra) = DISTANCE_CODES[index];
+    writer.write_code(index as u32, 5);
+    writer.write_bits((distance - base) as u32, extra);
+}
+
+/// A single fixed Huffman block, finding matches with hash chains over the last 32 KiB.
+fn deflate_fixed(data: &[u8], out: &mut Vec<u8>) {
+    let mut writer = BitWriter {
+        out,
+        buffer: 0,
+        count: 0,
+    };
+
+    // final block, fixed Huffman codes
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    let hash = |i: usize| {
+        let value = (data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32;
+        (value.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize
+    };
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; WINDOW_SIZE];
+    let insert = |i: usize, head: &mut Vec<usize>, prev: &mut Vec<usize>| {
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(i);
+            prev[i % WINDOW_SIZE] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut best_length = 0;
+        let mut best_distance = 0;
+
+        if i + MIN_MATCH <= data.len() {
+            let mut candidate = head[hash(i)];
+            let max_length = MAX_MATCH.min(data.len() - i);
+
+            for _ in 0..MAX_CHAIN {
+                // stale chain entries can point at or past `i`, so check that before subtracting
+                if candidate == usize::MAX || candidate >= i || i - candidate > WINDOW_SIZE {
+                    break;
+                }
+
+                let length = data[candidate..]
+                    .iter()
+                    .zip(&data[i..i + max_length])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                if length > best_length {
+                    best_length = length;
+                    best_distance = i - candidate;
+                    if length == max_length {
+                        break;
+                    }
+                }
+
+                candidate = prev[candidate % WINDOW_SIZE];
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            write_match(&mut writer, best_length, best_distance);
+            for j in i..i + best_length {
+                insert(j, &mut head, &mut prev);
+            }
+            i += best_length;
+        } else {
+            write_literal(&mut writer, data[i] as u32);
+            insert(i, &mut head, &mut prev);
+            i += 1;
+        }
+    }
+
+    // end of block
+    write_literal(&mut writer, 256);
+    writer.flush();
+}
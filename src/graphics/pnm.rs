@@ -0,0 +1,128 @@
+use std::{fs::File, io::Write, path::Path};
+
+use crate::graphics::{RenderTarget, color};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Binary (P6) RGB image.
+    Ppm,
+    /// Binary (P5) grayscale image.
+    Pgm,
+}
+
+impl Format {
+    fn magic_number(&self) -> &'static str {
+        match self {
+            Format::Ppm => "P6",
+            Format::Pgm => "P5",
+        }
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        match self {
+            Format::Ppm => 3,
+            Format::Pgm => 1,
+        }
+    }
+}
+
+/// A binary PPM or PGM image render target, written to `output_path` on `render`. Alpha is
+/// dropped since neither format can store it.
+#[derive(Debug)]
+pub struct Image {
+    output_path: String,
+    width: usize,
+    height: usize,
+    format: Format,
+    data: Vec<u8>,
+}
+
+impl RenderTarget for Image {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+
+        let start = (x as usize + y as usize * self.width) * self.format.bytes_per_pixel();
+
+        match self.format {
+            Format::Ppm => {
+                self.data[start..start + 3].copy_from_slice(&[color.r, color.g, color.b])
+            }
+            Format::Pgm => self.data[start] = color.luminance(),
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+
+        let start = (x as usize + y as usize * self.width) * self.format.bytes_per_pixel();
+
+        Some(match self.format {
+            Format::Ppm => color::RGBA {
+                r: self.data[start],
+                g: self.data[start + 1],
+                b: self.data[start + 2],
+                a: 255,
+            },
+            Format::Pgm => color::RGBA {
+                r: self.data[start],
+                g: self.data[start],
+                b: self.data[start],
+                a: 255,
+            },
+        })
+    }
+
+    fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_to_file(self.output_path.as_str())?;
+        Ok(())
+    }
+}
+
+impl Image {
+    pub fn new(output_path: &str, width: usize, height: usize, format: Format) -> Image {
+        Image {
+            output_path: output_path.to_string(),
+            width,
+            height,
+            format,
+            data: vec![0; width * height * format.bytes_per_pixel()],
+        }
+    }
+
+    pub fn write_to_file(&self, name: &str) -> std::io::Result<()> {
+        let mut file = File::create(Path::new(name))?;
+        file.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// The complete file, rows written top first since our y axis points up.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header = format!(
+            "{}\n{} {}\n255\n",
+            self.format.magic_number(),
+            self.width,
+            self.height
+        );
+
+        let mut bytes = header.into_bytes();
+        let row_length = self.width * self.format.bytes_per_pixel();
+        if row_length > 0 {
+            for row in self.data.chunks_exact(row_length).rev() {
+                bytes.extend_from_slice(row);
+            }
+        }
+        bytes
+    }
+}
@@ -0,0 +1,183 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::graphics::{RenderTarget, color, tga};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Uses the texel the coordinate falls in.
+    Nearest,
+    /// Blends the four texels around the coordinate.
+    Bilinear,
+}
+
+/// How coordinates outside of [0, 1] are mapped back onto the texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+/// Why an image couldn't be turned into a texture.
+#[derive(Debug)]
+pub enum TextureError {
+    Tga(tga::TgaError),
+    /// The image has no pixels to sample.
+    Empty,
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureError::Tga(err) => write!(f, "{}", err),
+            TextureError::Empty => write!(f, "texture image has no pixels"),
+        }
+    }
+}
+
+impl Error for TextureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TextureError::Tga(err) => Some(err),
+            TextureError::Empty => None,
+        }
+    }
+}
+
+impl From<tga::TgaError> for TextureError {
+    fn from(err: tga::TgaError) -> TextureError {
+        TextureError::Tga(err)
+    }
+}
+
+/// An image sampled with normalized (u, v) coordinates, where (0, 0) is the bottom left corner
+/// like OBJ texture coordinates.
+#[derive(Debug, Clone)]
+pub struct Texture {
+    width: usize,
+    height: usize,
+    pixels: Vec<color::RGBA>,
+    pub filter: Filter,
+    pub wrap_mode: WrapMode,
+}
+
+impl Texture {
+    /// `pixels` are stored row by row starting at the bottom. Panics if there are not exactly
+    /// `width * height` of them, or none at all.
+    pub fn new(width: usize, height: usize, pixels: Vec<color::RGBA>) -> Texture {
+        if pixels.len() != width * height || pixels.is_empty() {
+            panic!("Error In Texture: Expected {} pixels.", width * height);
+        }
+
+        Texture {
+            width,
+            height,
+            pixels,
+            filter: Filter::Bilinear,
+            wrap_mode: WrapMode::Repeat,
+        }
+    }
+
+    /// Copies the pixels of `image`, which must have at least one.
+    pub fn from_image(image: &tga::Image) -> Result<Texture, TextureError> {
+        let width = image.width();
+        let height = image.height();
+        if width == 0 || height == 0 {
+            return Err(TextureError::Empty);
+        }
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                pixels.push(image.get(x, y).unwrap_or(color::RGBA {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 255,
+                }));
+            }
+        }
+
+        Ok(Texture::new(width, height, pixels))
+    }
+
+    pub fn from_tga_file(path: &str) -> Result<Texture, TextureError> {
+        Texture::from_image(&tga::Image::from_file(path)?)
+    }
+
+    pub fn with_filter(mut self, filter: Filter) -> Texture {
+        self.filter = filter;
+        self
+    }
+
+    pub fn with_wrap_mode(mut self, wrap_mode: WrapMode) -> Texture {
+        self.wrap_mode = wrap_mode;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The texel at (x, y), wrapping coordinates outside of the texture.
+    pub fn texel(&self, x: i32, y: i32) -> color::RGBA {
+        let x = wrap(x, self.width, self.wrap_mode);
+        let y = wrap(y, self.height, self.wrap_mode);
+        self.pixels[x + y * self.width]
+    }
+
+    pub fn sample(&self, u: f32, v: f32) -> color::RGBA {
+        let x = u * self.width as f32;
+        let y = v * self.height as f32;
+
+        match self.filter {
+            Filter::Nearest => self.texel(x.floor() as i32, y.floor() as i32),
+            Filter::Bilinear => {
+                // texel centers sit at half coordinates
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (tx, ty) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i32, y0 as i32);
+
+                let bottom = lerp(&self.texel(x0, y0), &self.texel(x0 + 1, y0), tx);
+                let top = lerp(&self.texel(x0, y0 + 1), &self.texel(x0 + 1, y0 + 1), tx);
+                lerp(&bottom, &top, ty)
+            }
+        }
+    }
+}
+
+fn wrap(coordinate: i32, size: usize, wrap_mode: WrapMode) -> usize {
+    let size = size as i32;
+
+    let wrapped = match wrap_mode {
+        WrapMode::Repeat => coordinate.rem_euclid(size),
+        WrapMode::Clamp => coordinate.clamp(0, size - 1),
+        WrapMode::Mirror => {
+            let period = coordinate.rem_euclid(size * 2);
+            if period < size {
+                period
+            } else {
+                size * 2 - 1 - period
+            }
+        }
+    };
+
+    wrapped as usize
+}
+
+fn lerp(a: &color::RGBA, b: &color::RGBA, t: f32) -> color::RGBA {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+    color::RGBA {
+        r: channel(a.r, b.r),
+        g: channel(a.g, b.g),
+        b: channel(a.b, b.b),
+        a: channel(a.a, b.a),
+    }
+}
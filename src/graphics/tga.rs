@@ -1,10 +1,16 @@
-use std::{fs::File, io::Write, path::Path};
+use std::{
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::Path,
+};
 
 use bytemuck::{Pod, Zeroable};
 
 use crate::graphics::{RenderTarget, color};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 pub enum ColorType {
     RGB,
@@ -13,7 +19,7 @@ pub enum ColorType {
 }
 
 impl ColorType {
-    fn bytes_per_pixel(&self) -> u8 {
+    pub(crate) fn bytes_per_pixel(&self) -> u8 {
         match self {
             ColorType::GrayScale => 1,
             ColorType::RGB => 3,
@@ -23,7 +29,7 @@ impl ColorType {
 }
 
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImageType {
     UncompressedTrueColor,
     UncompressedGrayScale,
@@ -32,6 +38,20 @@ pub enum ImageType {
 }
 
 impl ImageType {
+    fn from_value(value: u8) -> Option<ImageType> {
+        match value {
+            2 => Some(ImageType::UncompressedTrueColor),
+            3 => Some(ImageType::UncompressedGrayScale),
+            10 => Some(ImageType::RleTrueColor),
+            11 => Some(ImageType::RleGrayScale),
+            _ => None,
+        }
+    }
+
+    fn is_rle(&self) -> bool {
+        matches!(self, ImageType::RleTrueColor | ImageType::RleGrayScale)
+    }
+
     fn get_value(&self) -> u8 {
         match self {
             ImageType::UncompressedTrueColor => 2,
@@ -42,6 +62,54 @@ impl ImageType {
     }
 }
 
+#[derive(Debug)]
+pub enum TgaError {
+    Io(io::Error),
+    /// The file ended before the header, ID field, color map or pixel data did.
+    Truncated,
+    /// Only true color (2, 10) and grayscale (3, 11) images can be read.
+    UnsupportedImageType(u8),
+    UnsupportedBitsPerPixel {
+        image_type: u8,
+        bits_per_pixel: u8,
+    },
+}
+
+impl fmt::Display for TgaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TgaError::Io(err) => write!(f, "error reading TGA file: {}", err),
+            TgaError::Truncated => write!(f, "TGA file is truncated"),
+            TgaError::UnsupportedImageType(image_type) => {
+                write!(f, "unsupported TGA image type {}", image_type)
+            }
+            TgaError::UnsupportedBitsPerPixel {
+                image_type,
+                bits_per_pixel,
+            } => write!(
+                f,
+                "unsupported {} bits per pixel for TGA image type {}",
+                bits_per_pixel, image_type
+            ),
+        }
+    }
+}
+
+impl Error for TgaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TgaError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TgaError {
+    fn from(err: io::Error) -> TgaError {
+        TgaError::Io(err)
+    }
+}
+
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct Header {
@@ -73,7 +141,8 @@ impl Header {
             width,
             height,
             bits_per_pixel: color_type.bytes_per_pixel() * 8,
-            image_descriptor: 0,
+            // the low bits hold the number of alpha bits per pixel
+            image_descriptor: if *color_type == ColorType::RGBA { 8 } else { 0 },
         }
     }
 }
@@ -83,6 +152,7 @@ pub struct Image {
     output_path: String,
     color_type: ColorType,
     header: Header,
+    id: Vec<u8>,
     data: Vec<u8>,
 }
 
@@ -116,10 +186,10 @@ impl RenderTarget for Image {
                 self.data[start + 2] = color.r;
             }
             ColorType::RGBA => {
-                self.data[start] = color.a;
-                self.data[start + 1] = color.b;
-                self.data[start + 2] = color.g;
-                self.data[start + 3] = color.r;
+                self.data[start] = color.b;
+                self.data[start + 1] = color.g;
+                self.data[start + 2] = color.r;
+                self.data[start + 3] = color.a;
             }
         }
     }
@@ -130,6 +200,10 @@ impl RenderTarget for Image {
             Ok(()) => Ok(()),
         }
     }
+
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        Image::get(self, x, y)
+    }
 }
 
 impl Image {
@@ -146,20 +220,255 @@ impl Image {
             output_path: output_path.to_string(),
             header: Header::new(width, height, &image_type, &color_type),
             color_type,
+            id: Vec::new(),
             data: vec![0; data_length],
         }
     }
 
+    /// Reads a true color or grayscale TGA file, either uncompressed or RLE compressed.
+    /// Rendering the image afterwards writes it back to the same path.
+    pub fn from_file(path: &str) -> Result<Image, TgaError> {
+        let bytes = fs::read(path)?;
+        Image::from_bytes(path, &bytes)
+    }
+
+    /// Parses the contents of a TGA file. `output_path` is where `render` writes the image.
+    pub fn from_bytes(output_path: &str, bytes: &[u8]) -> Result<Image, TgaError> {
+        let header_size = std::mem::size_of::<Header>();
+        let header: Header =
+            bytemuck::pod_read_unaligned(bytes.get(..header_size).ok_or(TgaError::Truncated)?);
+
+        let image_type = ImageType::from_value(header.image_type)
+            .ok_or(TgaError::UnsupportedImageType(header.image_type))?;
+
+        let color_type = match (image_type, header.bits_per_pixel) {
+            (ImageType::UncompressedTrueColor | ImageType::RleTrueColor, 24) => ColorType::RGB,
+            (ImageType::UncompressedTrueColor | ImageType::RleTrueColor, 32) => ColorType::RGBA,
+            (ImageType::UncompressedGrayScale | ImageType::RleGrayScale, 8) => ColorType::GrayScale,
+            _ => {
+                return Err(TgaError::UnsupportedBitsPerPixel {
+                    image_type: header.image_type,
+                    bits_per_pixel: header.bits_per_pixel,
+                });
+            }
+        };
+
+        // the ID field and any color map come between the header and the pixels
+        let id_end = header_size + header.id_length as usize;
+        let id = bytes.get(header_size..id_end).ok_or(TgaError::Truncated)?;
+
+        let color_map_length = if header.color_map_type != 0 {
+            header.cm_length as usize * (header.cm_entry_size as usize).div_ceil(8)
+        } else {
+            0
+        };
+        let pixels = bytes
+            .get(id_end + color_map_length..)
+            .ok_or(TgaError::Truncated)?;
+
+        let width = header.width as usize;
+        let height = header.height as usize;
+        let bpp = color_type.bytes_per_pixel() as usize;
+        let data_length = width * height * bpp;
+
+        let data = if image_type.is_rle() {
+            decode_rle(pixels, data_length, bpp)?
+        } else {
+            pixels
+                .get(..data_length)
+                .ok_or(TgaError::Truncated)?
+                .to_vec()
+        };
+
+        let mut image = Image {
+            output_path: output_path.to_string(),
+            header: Header::new(header.width, header.height, &image_type, &color_type),
+            color_type,
+            id: id.to_vec(),
+            data,
+        };
+
+        // keep pixels left to right and bottom to top like the images we write
+        if header.image_descriptor & 0x10 != 0 {
+            image.flip_horizontally();
+        }
+        if header.image_descriptor & 0x20 != 0 {
+            image.flip_vertically();
+        }
+
+        Ok(image)
+    }
+
+    /// The image ID field, which holds free-form data some tools store in the file.
+    pub fn id(&self) -> &[u8] {
+        &self.id
+    }
+
+    /// The color of a pixel, with (0, 0) in the bottom left corner. Grayscale pixels are returned
+    /// opaque.
+    pub fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        let width = self.width() as i32;
+        let height = self.height() as i32;
+
+        if self.data.is_empty() || x >= width || y >= height || x < 0 || y < 0 {
+            return None;
+        }
+
+        let bpp = self.color_type.bytes_per_pixel() as usize;
+        let start = (x as u32 + y as u32 * width as u32) as usize * bpp;
+        let pixel = &self.data[start..start + bpp];
+
+        Some(match self.color_type {
+            ColorType::GrayScale => color::RGBA {
+                r: pixel[0],
+                g: pixel[0],
+                b: pixel[0],
+                a: 255,
+            },
+            ColorType::RGB => color::RGBA {
+                r: pixel[2],
+                g: pixel[1],
+                b: pixel[0],
+                a: 255,
+            },
+            ColorType::RGBA => color::RGBA {
+                r: pixel[2],
+                g: pixel[1],
+                b: pixel[0],
+                a: pixel[3],
+            },
+        })
+    }
+
+    fn flip_horizontally(&mut self) {
+        let bpp = self.color_type.bytes_per_pixel() as usize;
+        let row_length = self.width() * bpp;
+
+        // an image 0 pixels wide has no data, so the chunk size only has to be non-zero
+        for row in self.data.chunks_exact_mut(row_length.max(1)) {
+            let width = row.len() / bpp;
+            for x in 0..width / 2 {
+                for byte in 0..bpp {
+                    row.swap(x * bpp + byte, (width - 1 - x) * bpp + byte);
+                }
+            }
+        }
+    }
+
+    fn flip_vertically(&mut self) {
+        let row_length = self.width() * self.color_type.bytes_per_pixel() as usize;
+        let height = self.height();
+
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - 1 - y) * row_length);
+            top[y * row_length..(y + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+        }
+    }
+
+    pub fn set_output_path(&mut self, output_path: &str) {
+        self.output_path = output_path.to_string();
+    }
+
     pub fn write_to_file(&self, name: &str) -> std::io::Result<()> {
         let path = Path::new(name);
-        let mut file = match File::create(&path) {
+        let mut file = match File::create(path) {
             Ok(file) => file,
             Err(_) => panic!("Error opening file"),
         };
 
-        let header: &[u8] = bytemuck::bytes_of(&self.header); // TODO: How does bytemuck work?
-        file.write_all(header)?;
-        file.write_all(&self.data[..])?;
+        file.write_all(&self.to_bytes())?;
         Ok(())
     }
+
+    /// The complete TGA file, run-length encoding the pixels if the image type is an RLE one.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let header: &[u8] = bytemuck::bytes_of(&self.header); // TODO: How does bytemuck work?
+        let mut bytes = header.to_vec();
+
+        let is_rle = ImageType::from_value(self.header.image_type).is_some_and(|t| t.is_rle());
+        if is_rle {
+            let row_length = self.width() * self.color_type.bytes_per_pixel() as usize;
+            let bpp = self.color_type.bytes_per_pixel() as usize;
+
+            // packets never span two rows, as the TGA 2.0 spec asks, and empty images have none
+            for row in self.data.chunks_exact(row_length.max(1)) {
+                encode_rle_row(row, bpp, &mut bytes);
+            }
+        } else {
+            bytes.extend_from_slice(&self.data);
+        }
+
+        bytes
+    }
+}
+
+/// Expands RLE packets into `length` bytes of raw pixel data. Each packet starts with a byte whose
+/// high bit marks a run of one repeated pixel, and whose low 7 bits hold the pixel count minus one.
+fn decode_rle(packets: &[u8], length: usize, bpp: usize) -> Result<Vec<u8>, TgaError> {
+    // the header's size can't be trusted, but every packet byte decodes to at most 128 pixels
+    let mut data = Vec::with_capacity(length.min(packets.len().saturating_mul(128 * bpp)));
+    let mut offset = 0;
+
+    while data.len() < length {
+        let packet = *packets.get(offset).ok_or(TgaError::Truncated)?;
+        let count = (packet & 0x7f) as usize + 1;
+        offset += 1;
+
+        if packet & 0x80 != 0 {
+            let pixel = packets
+                .get(offset..offset + bpp)
+                .ok_or(TgaError::Truncated)?;
+            for _ in 0..count {
+                data.extend_from_slice(pixel);
+            }
+            offset += bpp;
+        } else {
+            let pixels = packets
+                .get(offset..offset + count * bpp)
+                .ok_or(TgaError::Truncated)?;
+            data.extend_from_slice(pixels);
+            offset += count * bpp;
+        }
+    }
+
+    // a packet may not run past the end of the image, but be lenient with ones that do
+    data.truncate(length);
+    Ok(data)
+}
+
+/// Appends the RLE packets for one row of pixels to `out`. Repeated pixels become run packets of
+/// up to 128 pixels, and everything between runs is grouped into raw packets of the same size.
+fn encode_rle_row(row: &[u8], bpp: usize, out: &mut Vec<u8>) {
+    const MAX_PACKET: usize = 128;
+
+    let pixels: Vec<&[u8]> = row.chunks_exact(bpp).collect();
+    let mut i = 0;
+
+    while i < pixels.len() {
+        let mut run = 1;
+        while i + run < pixels.len() && run < MAX_PACKET && pixels[i + run] == pixels[i] {
+            run += 1;
+        }
+
+        if run > 1 {
+            out.push(0x80 | (run - 1) as u8);
+            out.extend_from_slice(pixels[i]);
+            i += run;
+            continue;
+        }
+
+        // collect raw pixels until the next run of two or more starts
+        let start = i;
+        while i < pixels.len() && i - start < MAX_PACKET {
+            if i + 1 < pixels.len() && pixels[i] == pixels[i + 1] {
+                break;
+            }
+            i += 1;
+        }
+
+        out.push((i - start - 1) as u8);
+        for pixel in &pixels[start..i] {
+            out.extend_from_slice(pixel);
+        }
+    }
 }
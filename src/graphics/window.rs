@@ -1,7 +1,10 @@
-use minifb::{Key, WindowOptions};
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, WindowOptions};
 
 use crate::graphics::{RenderTarget, color};
 
+// opaque black, so blended pixels composite against what is actually shown
+const CLEAR_COLOR: u32 = 0xff000000;
+
 pub struct TinyRendererWindow {
     width: usize,
     height: usize,
@@ -17,7 +20,7 @@ impl TinyRendererWindow {
         TinyRendererWindow {
             width,
             height,
-            buffer: vec![0; width * height],
+            buffer: vec![CLEAR_COLOR; width * height],
             window: minifb_window,
         }
     }
@@ -35,7 +38,26 @@ impl TinyRendererWindow {
     }
 
     pub fn clear(&mut self) {
-        self.buffer.fill(0);
+        self.buffer.fill(CLEAR_COLOR);
+    }
+
+    /// Whether `key` went down since the last frame, ignoring key repeat.
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.window.is_key_pressed(key, KeyRepeat::No)
+    }
+
+    pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+        self.window.get_mouse_down(button)
+    }
+
+    /// The mouse position in window pixels, which may lie outside the window while dragging.
+    pub fn mouse_pos(&self) -> Option<(f32, f32)> {
+        self.window.get_mouse_pos(MouseMode::Pass)
+    }
+
+    /// How far the scroll wheel moved since the last frame, if at all.
+    pub fn scroll_wheel(&self) -> Option<(f32, f32)> {
+        self.window.get_scroll_wheel()
     }
 }
 
@@ -61,6 +83,20 @@ impl RenderTarget for TinyRendererWindow {
         self.buffer[(y * self.width as i32 + x) as usize] = rgba_u32;
     }
 
+    fn get(&self, x: i32, y: i32) -> Option<color::RGBA> {
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            return None;
+        }
+
+        let pixel = self.buffer[(y * self.width as i32 + x) as usize];
+        Some(color::RGBA {
+            r: (pixel >> 16) as u8,
+            g: (pixel >> 8) as u8,
+            b: pixel as u8,
+            a: (pixel >> 24) as u8,
+        })
+    }
+
     fn render(&mut self) -> Result<(), Box<dyn std::error::Error>> { //TODO: why do i need to flip this here? is there a better way to do this?
         let mut flipped_buffer = vec![0u32; self.buffer.len()];
 
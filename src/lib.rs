@@ -1,5 +1,7 @@
 pub mod algorithms;
 pub mod geometry;
 pub mod graphics;
+pub mod lighting;
 pub mod mesh;
 pub mod renderer;
+pub mod shader;
@@ -0,0 +1,266 @@
+use crate::geometry::Vec3;
+use crate::graphics::color;
+
+/// How a point or spot light fades with distance: `1 / (constant + linear * d + quadratic * d^2)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attenuation {
+    pub constant: f32,
+    pub linear: f32,
+    pub quadratic: f32,
+}
+
+impl Default for Attenuation {
+    /// No falloff at all.
+    fn default() -> Attenuation {
+        Attenuation::new(1.0, 0.0, 0.0)
+    }
+}
+
+impl Attenuation {
+    pub fn new(constant: f32, linear: f32, quadratic: f32) -> Attenuation {
+        Attenuation {
+            constant,
+            linear,
+            quadratic,
+        }
+    }
+
+    pub fn factor(&self, distance: f32) -> f32 {
+        let denominator =
+            self.constant + self.linear * distance + self.quadratic * distance * distance;
+        if denominator <= 0.0 {
+            return 1.0;
+        }
+
+        1.0 / denominator
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    /// Parallel rays travelling along `direction`, like sunlight.
+    Directional { direction: Vec3<f32> },
+    /// Shines equally in every direction from `position`.
+    Point { position: Vec3<f32> },
+    /// A cone of light from `position` along `direction`. Full strength inside `inner_angle` of
+    /// the axis, fading out to nothing at `outer_angle`. Both are half angles in radians.
+    Spot {
+        position: Vec3<f32>,
+        direction: Vec3<f32>,
+        inner_angle: f32,
+        outer_angle: f32,
+    },
+}
+
+/// A light in world space. Attenuation only applies to point and spot lights.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    pub color: color::RGBA,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+}
+
+impl Light {
+    pub fn directional(direction: Vec3<f32>, color: color::RGBA) -> Light {
+        Light::new(LightKind::Directional { direction }, color)
+    }
+
+    pub fn point(position: Vec3<f32>, color: color::RGBA) -> Light {
+        Light::new(LightKind::Point { position }, color)
+    }
+
+    pub fn spot(
+        position: Vec3<f32>,
+        direction: Vec3<f32>,
+        inner_angle: f32,
+        outer_angle: f32,
+        color: color::RGBA,
+    ) -> Light {
+        Light::new(
+            LightKind::Spot {
+                position,
+                direction,
+                inner_angle,
+                outer_angle,
+            },
+            color,
+        )
+    }
+
+    pub fn with_intensity(mut self, intensity: f32) -> Light {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn with_attenuation(mut self, attenuation: Attenuation) -> Light {
+        self.attenuation = attenuation;
+        self
+    }
+
+    fn new(kind: LightKind, color: color::RGBA) -> Light {
+        Light {
+            kind,
+            color,
+            intensity: 1.0,
+            attenuation: Attenuation::default(),
+        }
+    }
+
+    /// The unit direction from `position` towards the light and how strongly it arrives there,
+    /// before the angle to the surface is taken into account.
+    fn incidence(&self, position: &Vec3<f32>) -> (Vec3<f32>, f32) {
+        match self.kind {
+            LightKind::Directional { direction } => {
+                (direction.normalized().scaled(-1.0), self.intensity)
+            }
+            LightKind::Point { position: source } => {
+                let offset = &source - position;
+                let distance = offset.length();
+                (
+                    offset.normalized(),
+                    self.intensity * self.attenuation.factor(distance),
+                )
+            }
+            LightKind::Spot {
+                position: source,
+                direction,
+                inner_angle,
+                outer_angle,
+            } => {
+                let offset = &source - position;
+                let distance = offset.length();
+                let to_light = offset.normalized();
+
+                // cosine of the angle between the cone's axis and the ray reaching the point
+                let cos_angle = &to_light.scaled(-1.0) * &direction.normalized();
+                let (cos_inner, cos_outer) = (inner_angle.cos(), outer_angle.cos());
+                let cone = if cos_inner > cos_outer {
+                    ((cos_angle - cos_outer) / (cos_inner - cos_outer)).clamp(0.0, 1.0)
+                } else if cos_angle >= cos_outer {
+                    1.0
+                } else {
+                    0.0
+                };
+
+                (
+                    to_light,
+                    self.intensity * self.attenuation.factor(distance) * cone,
+                )
+            }
+        }
+    }
+}
+
+/// How a surface responds to light. The diffuse color comes from `Uniforms::color` or the mesh's
+/// diffuse texture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    pub specular: color::RGBA,
+    /// The Blinn-Phong exponent. Higher values give smaller, sharper highlights.
+    pub shininess: f32,
+}
+
+impl Default for Material {
+    fn default() -> Material {
+        Material {
+            specular: color::RGBA {
+                r: 128,
+                g: 128,
+                b: 128,
+                a: 255,
+            },
+            shininess: 32.0,
+        }
+    }
+}
+
+/// Where lighting is evaluated when a mesh is filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadingModel {
+    /// Once per face, at its center with the face normal.
+    Flat,
+    /// At each vertex, interpolated across the face.
+    Gouraud,
+    /// At each pixel, from the interpolated vertex normal.
+    Phong,
+}
+
+/// Light reaching a surface point, kept as diffuse and specular parts so it can be interpolated
+/// before being applied to a textured color. Channels are in [0, 1] per unit of light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Illumination {
+    pub diffuse: Vec3<f32>,
+    pub specular: Vec3<f32>,
+}
+
+impl Illumination {
+    /// Modulates `base` by the diffuse light and adds the specular highlight on top. Alpha is
+    /// left as it is.
+    pub fn apply(&self, base: &color::RGBA) -> color::RGBA {
+        let channel = |base: u8, diffuse: f32, specular: f32| {
+            ((base as f32 / 255.0 * diffuse + specular) * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+
+        color::RGBA {
+            r: channel(base.r, self.diffuse.x, self.specular.x),
+            g: channel(base.g, self.diffuse.y, self.specular.y),
+            b: channel(base.b, self.diffuse.z, self.specular.z),
+            a: base.a,
+        }
+    }
+}
+
+/// Sums the ambient light, Lambert diffuse and Blinn-Phong specular from every light at a world
+/// space `position` with unit `normal`, seen from `eye`. A zero normal only gets ambient light.
+pub fn illuminate(
+    lights: &[Light],
+    ambient_light: &color::RGBA,
+    material: &Material,
+    position: &Vec3<f32>,
+    normal: &Vec3<f32>,
+    eye: &Vec3<f32>,
+) -> Illumination {
+    let mut illumination = Illumination {
+        diffuse: rgb(ambient_light),
+        specular: Vec3::new(0.0, 0.0, 0.0),
+    };
+    if normal.length() == 0.0 {
+        return illumination;
+    }
+
+    let to_eye = (eye - position).normalized();
+    let specular_color = rgb(&material.specular);
+
+    for light in lights {
+        let (to_light, strength) = light.incidence(position);
+        let lambert = normal * &to_light;
+        if strength <= 0.0 || lambert <= 0.0 {
+            continue;
+        }
+
+        let light_color = rgb(&light.color).scaled(strength);
+        illumination.diffuse = &illumination.diffuse + &light_color.scaled(lambert);
+
+        let half_vector = (&to_light + &to_eye).normalized();
+        let highlight = (normal * &half_vector).max(0.0).powf(material.shininess);
+        let specular = multiply(&light_color, &specular_color).scaled(highlight);
+        illumination.specular = &illumination.specular + &specular;
+    }
+
+    illumination
+}
+
+fn rgb(color: &color::RGBA) -> Vec3<f32> {
+    Vec3::new(
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+    )
+}
+
+fn multiply(a: &Vec3<f32>, b: &Vec3<f32>) -> Vec3<f32> {
+    Vec3::new(a.x * b.x, a.y * b.y, a.z * b.z)
+}
@@ -1,32 +1,30 @@
-mod algorithms;
-mod geometry;
-mod graphics;
-mod mesh;
-mod renderer;
-
 use std::env;
 use std::process;
+use std::rc::Rc;
 
-use minifb;
 use rand::Rng;
 
-use crate::algorithms::line_alg_with_floats;
-use crate::algorithms::triangle_raster::rasterize_triangle_scanline;
-use crate::algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle};
+use tiny_renderer::algorithms::line_alg_with_floats;
+use tiny_renderer::algorithms::triangle_raster::rasterize_triangle_scanline;
+use tiny_renderer::algorithms::{
+    Algorithms, LineCap, LineJoin, LineStyle, rasterize_triangle, xiaolin_wu_line_alg,
+};
 
-use crate::geometry::Vec4;
-use crate::graphics::color;
-use crate::graphics::{TinyRendererWindow, tga};
+use tiny_renderer::geometry::{Vec3, Vec4};
+use tiny_renderer::graphics::color;
+use tiny_renderer::graphics::{Texture, TinyRendererWindow, image_for_path, tga};
 
-use crate::mesh::{FaceElement, Mesh};
+use tiny_renderer::mesh::{FaceElement, Mesh};
 
-use crate::renderer::{DrawType, TinyRendererBuilder};
+use tiny_renderer::renderer::{Camera, CameraControls, CullMode, DrawType, TinyRendererBuilder};
+use tiny_renderer::shader::TextureShader;
 
-static USAGE_STATEMENT: &'static str = "USAGE: tiny_renderer [run_type]";
+static USAGE_STATEMENT: &str = "USAGE: tiny_renderer [run_type] [output_path]";
 
 const WIDTH: usize = 1000;
 const HEIGHT: usize = 1000;
 const TARGET_FPS: usize = 120;
+const DEFAULT_OUTPUT_PATH: &str = "tga/img.tga";
 
 enum RunType {
     Window,
@@ -35,11 +33,12 @@ enum RunType {
 
 struct Config {
     run_type: RunType,
+    output_path: String,
 }
 
 impl Config {
-    pub fn build(args: &Vec<String>) -> Config {
-        if args.len() != 2 {
+    pub fn build(args: &[String]) -> Config {
+        if args.len() != 2 && args.len() != 3 {
             panic!("{}", USAGE_STATEMENT);
         }
 
@@ -53,11 +52,19 @@ impl Config {
             panic!("{}", USAGE_STATEMENT);
         }
 
-        Config { run_type }
+        // the image encoder is picked from the output file's extension
+        let output_path = args
+            .get(2)
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_OUTPUT_PATH.to_string());
+
+        Config {
+            run_type,
+            output_path,
+        }
     }
 }
 
-//TODO: fix the triangle rasterization not working on some obj
 fn main() {
     let args: Vec<String> = env::args().collect();
     let config = Config::build(&args);
@@ -67,7 +74,7 @@ fn main() {
             render_window();
         }
         RunType::Image => {
-            render_meshes_to_image();
+            render_meshes_to_image(&config.output_path);
         }
     }
 }
@@ -77,8 +84,15 @@ fn render_window() {
     let mut window_renderer = TinyRendererBuilder::new()
         .with_render_output(TinyRendererWindow::new(WIDTH, HEIGHT))
         .with_target_fps(TARGET_FPS)
+        .with_camera_controls(CameraControls::Orbit)
+        .with_camera(Camera::perspective(
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(0.0, 0.0, 0.0),
+            std::f32::consts::FRAC_PI_3,
+        ))
         .with_color(color::PURPLE)
-        .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+        // anti-aliased wireframes, blended into the window's pixels
+        .with_algorithms(Algorithms::new(xiaolin_wu_line_alg, rasterize_triangle))
         .build();
 
     // load our mesh into memory
@@ -90,8 +104,8 @@ fn render_window() {
     // add the mesh into the renderer, getting back it's id
     let body_id = window_renderer.add_mesh(body_mesh);
     window_renderer.set_draw_type(body_id, DrawType::Line);
-    window_renderer.scale_vertices(body_id, 0.05);
-    window_renderer.move_vertices(body_id, 0.0, 1.0);
+    window_renderer.set_scale(body_id, Vec3::new(0.05, 0.05, 0.05));
+    window_renderer.set_translation(body_id, Vec3::new(0.0, 1.0, 0.0));
 
     // ok, now do it again
     let mesh = Mesh::from_obj_file("obj/head.obj").unwrap_or_else(|err| {
@@ -101,10 +115,29 @@ fn render_window() {
 
     let head_mesh_id = window_renderer.add_mesh(mesh);
     window_renderer.set_draw_type(head_mesh_id, DrawType::Line);
-    window_renderer.scale_vertices(head_mesh_id, 0.5);
+    window_renderer.set_scale(head_mesh_id, Vec3::new(0.5, 0.5, 0.5));
+    // the head is closed, so the faces turned away from the camera never need drawing
+    window_renderer.set_cull_mode(head_mesh_id, CullMode::Back);
+
+    // the diffuse texture isn't checked in, so only show it when it's there
+    if let Ok(texture) = Texture::from_tga_file("obj/head_diffuse.tga") {
+        window_renderer.set_draw_type(head_mesh_id, DrawType::Fill);
+        window_renderer.set_shader(head_mesh_id, TextureShader);
+        window_renderer.set_texture(head_mesh_id, Rc::new(texture));
+    }
 
     while window_renderer.is_open() && !window_renderer.is_key_down(minifb::Key::Escape) {
-        window_renderer.move_vertices(body_id, 0.0, -0.04);
+        let mut body_translation = window_renderer.translation(body_id);
+        body_translation.y -= 0.04;
+        window_renderer.set_translation(body_id, body_translation);
+
+        // spin the head so it can be seen from every side
+        let mut head_rotation = window_renderer.rotation(head_mesh_id);
+        head_rotation.y += 0.02;
+        window_renderer.set_rotation(head_mesh_id, head_rotation);
+
+        // drag to orbit, scroll to zoom, tab to switch to WASD fly controls
+        window_renderer.update_camera();
 
         window_renderer.clear();
 
@@ -115,16 +148,15 @@ fn render_window() {
     }
 }
 
-fn render_meshes_to_image() {
-    // create a renderer with a tga image output instead of a window
+fn render_meshes_to_image(output_path: &str) {
+    // create a renderer with an image output instead of a window
+    let image = image_for_path(output_path, 3000, 3000).unwrap_or_else(|err| {
+        eprintln!("Error creating the image: {}", err);
+        process::exit(1);
+    });
+
     let mut renderer = TinyRendererBuilder::new()
-        .with_render_output(tga::Image::new(
-            "tga/img.tga",
-            3000,
-            3000,
-            tga::ImageType::UncompressedTrueColor,
-            tga::ColorType::RGB,
-        ))
+        .with_render_output(image)
         .with_color(color::GREEN)
         .with_algorithms(Algorithms::new(
             line_alg_with_floats,
@@ -137,24 +169,21 @@ fn render_meshes_to_image() {
         process::exit(1);
     });
 
-    let body_mesh_2 = body_mesh.clone();
-    let body_mesh_3 = body_mesh.clone();
-
-    // bring in more meshes!
+    // bring in more meshes! the instances share the body's vertex data
     let body_id = renderer.add_mesh(body_mesh);
     renderer.set_draw_type(body_id, DrawType::Line);
-    renderer.scale_vertices(body_id, 0.05);
-    renderer.move_vertices(body_id, 0.0, -1.2);
+    renderer.set_scale(body_id, Vec3::new(0.05, 0.05, 0.05));
+    renderer.set_translation(body_id, Vec3::new(0.0, -1.2, 0.0));
 
-    let body_id_2 = renderer.add_mesh(body_mesh_2);
+    let body_id_2 = renderer.add_mesh_instance(body_id);
     renderer.set_draw_type(body_id_2, DrawType::Line);
-    renderer.scale_vertices(body_id_2, 0.1);
-    renderer.move_vertices(body_id_2, -1.0, -1.0);
+    renderer.set_scale(body_id_2, Vec3::new(0.1, 0.1, 0.1));
+    renderer.set_translation(body_id_2, Vec3::new(-1.0, -1.0, 0.0));
 
-    let body_id_3 = renderer.add_mesh(body_mesh_3);
+    let body_id_3 = renderer.add_mesh_instance(body_id);
     renderer.set_draw_type(body_id_3, DrawType::Line);
-    renderer.scale_vertices(body_id_3, 0.1);
-    renderer.move_vertices(body_id_3, 1.0, -1.0);
+    renderer.set_scale(body_id_3, Vec3::new(0.1, 0.1, 0.1));
+    renderer.set_translation(body_id_3, Vec3::new(1.0, -1.0, 0.0));
 
     // read in a mesh from our obj file
     let mesh = Mesh::from_obj_file("obj/head.obj").unwrap_or_else(|err| {
@@ -164,7 +193,13 @@ fn render_meshes_to_image() {
 
     let head_mesh_id = renderer.add_mesh(mesh);
     renderer.set_draw_type(head_mesh_id, DrawType::Line);
-    renderer.scale_vertices(head_mesh_id, 0.5);
+    renderer.set_scale(head_mesh_id, Vec3::new(0.5, 0.5, 0.5));
+
+    // 1 pixel lines all but disappear once a 3000x3000 image is scaled down
+    let line_style = LineStyle::new(3.0, LineCap::Round, LineJoin::Miter);
+    for id in [body_id, body_id_2, body_id_3, head_mesh_id] {
+        renderer.set_line_style(id, line_style);
+    }
 
     // call our draw function once since it's just a single image
     if let Err(err) = renderer.draw() {
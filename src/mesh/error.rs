@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Why an OBJ or MTL file, or a texture it names, couldn't be loaded. In lenient mode the same
+/// errors are collected as warnings instead.
+#[derive(Debug)]
+pub enum MeshLoadError {
+    Io {
+        file: PathBuf,
+        source: io::Error,
+    },
+    /// A line that couldn't be understood. `line` and `column` are 1-based, and `column` points at
+    /// the offending token, or just past the end of the line when one is missing.
+    Parse {
+        file: PathBuf,
+        line: usize,
+        column: usize,
+        text: String,
+        message: String,
+    },
+    /// A texture map that couldn't be read as a TGA image.
+    Texture {
+        file: PathBuf,
+        source: Box<dyn Error>,
+    },
+}
+
+impl fmt::Display for MeshLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeshLoadError::Io { file, source } => {
+                write!(f, "error reading {}: {}", file.display(), source)
+            }
+            MeshLoadError::Parse {
+                file,
+                line,
+                column,
+                text,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{}: {} in \"{}\"",
+                file.display(),
+                line,
+                column,
+                message,
+                text
+            ),
+            MeshLoadError::Texture { file, source } => {
+                write!(f, "error loading texture {}: {}", file.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for MeshLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MeshLoadError::Io { source, .. } => Some(source),
+            MeshLoadError::Texture { source, .. } => Some(source.as_ref()),
+            MeshLoadError::Parse { .. } => None,
+        }
+    }
+}
+
+/// What went wrong on a line, before the loader adds which file and line it was.
+#[derive(Debug)]
+pub(super) struct LineError {
+    /// 1-based, like `MeshLoadError::Parse::column`.
+    pub column: usize,
+    pub message: String,
+}
+
+impl LineError {
+    /// An error at `token`, which must be a slice of `line`.
+    pub fn at(line: &str, token: &str, message: String) -> LineError {
+        LineError {
+            column: token.as_ptr() as usize - line.as_ptr() as usize + 1,
+            message,
+        }
+    }
+
+    /// An error for something missing from the end of `line`.
+    pub fn missing(line: &str, what: &str) -> LineError {
+        LineError {
+            column: line.trim_end().len() + 1,
+            message: format!("missing {}", what),
+        }
+    }
+
+    /// The `MeshLoadError` for this error on line `line_number` of `file`, whose text is `line`.
+    pub fn into_load_error(self, file: &Path, line_number: usize, line: &str) -> MeshLoadError {
+        MeshLoadError::Parse {
+            file: file.to_path_buf(),
+            line: line_number,
+            column: self.column,
+            text: line.to_string(),
+            message: self.message,
+        }
+    }
+}
+
+/// Collects problems as warnings in lenient mode, or turns the first into an error in strict mode.
+pub(super) struct Diagnostics {
+    pub strict: bool,
+    pub warnings: Vec<MeshLoadError>,
+}
+
+impl Diagnostics {
+    /// Returns the error in strict mode, so the caller can give up with `?`, and keeps it as a
+    /// warning otherwise, so the caller can skip whatever caused it.
+    pub fn report(&mut self, error: MeshLoadError) -> Result<(), MeshLoadError> {
+        if self.strict {
+            return Err(error);
+        }
+
+        self.warnings.push(error);
+        Ok(())
+    }
+
+    /// Keeps the error as a warning in either mode, for things that are skipped even when loading
+    /// strictly.
+    pub fn warn(&mut self, error: MeshLoadError) {
+        self.warnings.push(error);
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+
+use crate::geometry::{Vec3, Vec4};
+use crate::mesh::error::Diagnostics;
+use crate::mesh::obj::{self, LineReader, Statement};
+use crate::mesh::triangulate::polygon_triangles;
+use crate::mesh::{Mesh, MeshLoadError, ParseMode};
+
+/// Stands in for the texture coordinate or normal of a corner that has none.
+pub const NO_INDEX: u32 = u32::MAX;
+
+/// A triangle mesh read straight into flat buffers, for files too big to load as a `Mesh`.
+/// Every three entries of `vertex_indices` are a triangle, and `texture_indices` and
+/// `normal_indices` run alongside it with `NO_INDEX` where a corner has none. All indices are
+/// 0-based.
+///
+/// Flat meshes are only for loading. The renderer draws `Mesh`es, so they are meant for code
+/// that hands the buffers to a pipeline of its own or only needs the geometry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlatMesh {
+    pub vertices: Vec<Vec4<f32>>,
+    pub texture_coordinates: Vec<Vec3<f32>>,
+    pub vertex_normals: Vec<Vec3<f32>>,
+    pub vertex_indices: Vec<u32>,
+    pub texture_indices: Vec<u32>,
+    pub normal_indices: Vec<u32>,
+}
+
+impl FlatMesh {
+    /// Streams an OBJ file through a buffered reader. See `from_obj_reader`.
+    pub fn from_obj_file(
+        obj_file_path: &str,
+        mode: ParseMode,
+    ) -> Result<(FlatMesh, Vec<MeshLoadError>), MeshLoadError> {
+        let file = PathBuf::from(obj_file_path);
+        let reader = File::open(&file).map_err(|source| MeshLoadError::Io {
+            file: file.clone(),
+            source,
+        })?;
+
+        FlatMesh::read(BufReader::new(reader), file, mode)
+    }
+
+    /// Reads vertices, texture coordinates, normals and faces one line at a time, splitting
+    /// faces into triangles as they come. Lines are parsed by the same code as in
+    /// `Mesh::load_obj`, but materials, objects, groups and smoothing groups are ignored. Errors
+    /// from a reader have an empty `file`.
+    pub fn from_obj_reader<R: BufRead>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(FlatMesh, Vec<MeshLoadError>), MeshLoadError> {
+        FlatMesh::read(reader, PathBuf::new(), mode)
+    }
+
+    /// Reads OBJ text that is already in memory. See `from_obj_reader`.
+    pub fn from_obj_str(
+        obj: &str,
+        mode: ParseMode,
+    ) -> Result<(FlatMesh, Vec<MeshLoadError>), MeshLoadError> {
+        FlatMesh::from_obj_reader(obj.as_bytes(), mode)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.vertex_indices.len() / 3
+    }
+
+    fn read<R: BufRead>(
+        reader: R,
+        file: PathBuf,
+        mode: ParseMode,
+    ) -> Result<(FlatMesh, Vec<MeshLoadError>), MeshLoadError> {
+        let mut diagnostics = Diagnostics {
+            strict: mode == ParseMode::Strict,
+            warnings: Vec::new(),
+        };
+        let mut mesh = FlatMesh::default();
+
+        // reused for every face so that reading allocates only when the buffers grow
+        let mut corners = Vec::new();
+        let mut positions = Vec::new();
+
+        let mut lines = LineReader::new(reader);
+        while let Some((line_number, line)) =
+            lines.next_line().map_err(|source| MeshLoadError::Io {
+                file: file.clone(),
+                source,
+            })?
+        {
+            let counts = [
+                mesh.vertices.len(),
+                mesh.texture_coordinates.len(),
+                mesh.vertex_normals.len(),
+            ];
+
+            match obj::parse_line(line, counts, &mut corners) {
+                Ok(Statement::Vertex(vertex)) => mesh.vertices.push(vertex),
+                Ok(Statement::TextureCoordinate(coordinate)) => {
+                    mesh.texture_coordinates.push(coordinate)
+                }
+                Ok(Statement::VertexNormal(normal)) => mesh.vertex_normals.push(normal),
+                Ok(Statement::Face) => mesh.push_face(&corners, &mut positions),
+                Ok(Statement::Other { .. }) => {}
+                Err(error) => {
+                    diagnostics.report(error.into_load_error(&file, line_number, line))?
+                }
+            }
+        }
+
+        Ok((mesh, diagnostics.warnings))
+    }
+
+    /// Appends the triangles of a face read by `obj::parse_line`.
+    fn push_face(&mut self, corners: &[[u32; 3]], positions: &mut Vec<Vec3<f32>>) {
+        if corners.len() == 3 {
+            self.push_triangle([corners[0], corners[1], corners[2]]);
+            return;
+        }
+
+        positions.clear();
+        positions.extend(corners.iter().map(|corner| {
+            let vertex = self.vertices[corner[0] as usize];
+            Vec3::new(vertex.x, vertex.y, vertex.z)
+        }));
+        for [a, b, c] in polygon_triangles(positions) {
+            self.push_triangle([corners[a], corners[b], corners[c]]);
+        }
+    }
+
+    fn push_triangle(&mut self, corners: [[u32; 3]; 3]) {
+        for [vertex, texture, normal] in corners {
+            self.vertex_indices.push(vertex);
+            self.texture_indices.push(texture);
+            self.normal_indices.push(normal);
+        }
+    }
+}
+
+/// Rebuilds a `Mesh` with one `Vec` per triangle, which gives up what the flat buffers save. Load
+/// meshes that are going to be drawn with `Mesh::load_obj` instead.
+impl From<FlatMesh> for Mesh {
+    fn from(flat: FlatMesh) -> Mesh {
+        let faces = (0..flat.triangle_count())
+            .map(|triangle| {
+                (0..3)
+                    .map(|corner| {
+                        let i = triangle * 3 + corner;
+                        obj::face_element([
+                            flat.vertex_indices[i],
+                            flat.texture_indices[i],
+                            flat.normal_indices[i],
+                        ])
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Mesh {
+            vertices: flat.vertices,
+            vertex_normals: flat.vertex_normals,
+            texture_coordinates: flat.texture_coordinates,
+            faces,
+            ..Mesh::new()
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+use crate::mesh::Mesh;
+use crate::mesh::error::LineError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    /// From an `o` statement.
+    Object,
+    /// From a `g` statement.
+    Group,
+}
+
+/// A named run of consecutive faces. A name can have several ranges when a group is reopened
+/// later in the file, and faces can belong to an object and several groups at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaceGroup {
+    pub name: String,
+    pub kind: GroupKind,
+    pub faces: Range<usize>,
+}
+
+impl Mesh {
+    /// The face ranges of every object and group called `name`.
+    pub fn group_faces(&self, name: &str) -> impl Iterator<Item = Range<usize>> {
+        self.groups
+            .iter()
+            .filter(move |group| group.name == name)
+            .map(|group| group.faces.clone())
+    }
+
+    /// The name of every object and group, in the order they end in the file, without repeats.
+    pub fn group_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for group in &self.groups {
+            if !names.contains(&group.name.as_str()) {
+                names.push(&group.name);
+            }
+        }
+
+        names
+    }
+
+    /// Whether two faces may share smooth vertex normals. Faces in smoothing group 0 only share
+    /// with themselves, and meshes without smoothing groups share everywhere.
+    pub fn is_smoothed_with(&self, face: usize, other: usize) -> bool {
+        if self.smoothing_groups.len() != self.faces.len() || face == other {
+            return true;
+        }
+
+        let group = self.smoothing_groups[face];
+        group != 0 && group == self.smoothing_groups[other]
+    }
+
+    /// Starts groups of `kind` at the next face for an `o` or `g` statement, after ending
+    /// `current`. A `g` without names starts the group `default`.
+    pub(super) fn start_groups(
+        &mut self,
+        kind: GroupKind,
+        names: &[&str],
+        current: Vec<FaceGroup>,
+    ) -> Vec<FaceGroup> {
+        self.end_groups(current);
+
+        let start = self.faces.len();
+        let names = match (kind, names) {
+            (GroupKind::Group, []) => &["default"][..],
+            _ => names,
+        };
+        names
+            .iter()
+            .map(|name| FaceGroup {
+                name: name.to_string(),
+                kind,
+                faces: start..start,
+            })
+            .collect()
+    }
+
+    /// Ends groups after the last face read so far. Groups without faces are dropped.
+    pub(super) fn end_groups(&mut self, groups: Vec<FaceGroup>) {
+        for mut group in groups {
+            group.faces.end = self.faces.len();
+            if !group.faces.is_empty() {
+                self.groups.push(group);
+            }
+        }
+    }
+
+    /// Reads the group id of an `s` statement, where `off` is 0.
+    pub(super) fn parse_smoothing_group(line: &str) -> Result<u32, LineError> {
+        match line.split_whitespace().nth(1) {
+            Some("off") => Ok(0),
+            Some(token) => token.parse().map_err(|_| {
+                LineError::at(
+                    line,
+                    token,
+                    format!("invalid smoothing group \"{}\"", token),
+                )
+            }),
+            None => Err(LineError::missing(line, "smoothing group")),
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::geometry::Vec3;
+use crate::graphics::{Texture, color};
+use crate::lighting::Material;
+use crate::mesh::error::{Diagnostics, LineError};
+use crate::mesh::{Mesh, MeshLoadError, ObjLoadOptions, ParseMode};
+
+/// A material from an MTL file. Colors are RGB in [0, 1] and texture paths are relative to the
+/// working directory, resolved from the MTL file's location.
+#[derive(Debug, Clone)]
+pub struct MtlMaterial {
+    pub name: String,
+    /// `Ka`, parsed but not used by the built in shaders, which light with the diffuse color.
+    pub ambient: Vec3<f32>,
+    /// `Kd`
+    pub diffuse: Vec3<f32>,
+    /// `Ks`
+    pub specular: Vec3<f32>,
+    /// `Ns`, the specular exponent.
+    pub shininess: f32,
+    /// `d`, or 1 - `Tr`. 1 is fully opaque.
+    pub dissolve: f32,
+    /// `illum`. Models 0 and 1 have no specular highlights.
+    pub illumination_model: u32,
+    /// `map_Kd`
+    pub diffuse_map: Option<PathBuf>,
+    /// `map_Bump` or `bump`
+    pub bump_map: Option<PathBuf>,
+    /// `map_Ks`
+    pub specular_map: Option<PathBuf>,
+    /// The image at `diffuse_map`, loaded with the mesh when it is a TGA file.
+    pub diffuse_texture: Option<Rc<Texture>>,
+}
+
+impl MtlMaterial {
+    /// A material with the defaults used for anything its MTL entry leaves out: a light gray,
+    /// opaque surface without highlights.
+    pub fn new(name: &str) -> MtlMaterial {
+        MtlMaterial {
+            name: name.to_string(),
+            ambient: Vec3::new(0.2, 0.2, 0.2),
+            diffuse: Vec3::new(0.8, 0.8, 0.8),
+            specular: Vec3::new(0.0, 0.0, 0.0),
+            shininess: 1.0,
+            dissolve: 1.0,
+            illumination_model: 2,
+            diffuse_map: None,
+            bump_map: None,
+            specular_map: None,
+            diffuse_texture: None,
+        }
+    }
+
+    /// Reads every material in an MTL file with the default `ObjLoadOptions`, failing on the first
+    /// problem. Use `load_mtl` to skip whatever can't be parsed instead.
+    pub fn from_mtl_file(mtl_file_path: &Path) -> Result<Vec<MtlMaterial>, MeshLoadError> {
+        let (materials, _) = MtlMaterial::load_mtl(mtl_file_path, &ObjLoadOptions::default())?;
+        Ok(materials)
+    }
+
+    /// Reads every material in an MTL file and loads their diffuse textures, skipping maps that
+    /// aren't TGA images. Returns the materials and, in lenient mode, the problems that were
+    /// skipped.
+    pub fn load_mtl(
+        mtl_file_path: &Path,
+        options: &ObjLoadOptions,
+    ) -> Result<(Vec<MtlMaterial>, Vec<MeshLoadError>), MeshLoadError> {
+        let mut diagnostics = Diagnostics {
+            strict: options.mode == ParseMode::Strict,
+            warnings: Vec::new(),
+        };
+        let materials = MtlMaterial::read_mtl(mtl_file_path, &mut diagnostics)?;
+
+        Ok((materials, diagnostics.warnings))
+    }
+
+    /// Reads an MTL file for `load_mtl` or an OBJ file's `mtllib`, reporting problems to
+    /// `diagnostics`. Only a file that can't be read at all, or any problem in strict mode, is an
+    /// error.
+    pub(super) fn read_mtl(
+        mtl_file_path: &Path,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<Vec<MtlMaterial>, MeshLoadError> {
+        let mtl_content =
+            fs::read_to_string(mtl_file_path).map_err(|source| MeshLoadError::Io {
+                file: mtl_file_path.to_path_buf(),
+                source,
+            })?;
+        let directory = mtl_file_path.parent().unwrap_or(Path::new(""));
+        let mut materials: Vec<MtlMaterial> = Vec::new();
+
+        for (index, line) in mtl_content.lines().enumerate() {
+            if let Err(error) = parse_statement(line, directory, &mut materials) {
+                diagnostics.report(error.into_load_error(mtl_file_path, index + 1, line))?;
+            }
+        }
+
+        for material in &mut materials {
+            // only TGA images can be read, so materials with other maps are left untextured
+            let Some(path) = material.diffuse_map.as_ref().filter(|path| is_tga(path)) else {
+                continue;
+            };
+
+            let error = match Texture::from_tga_file(&path.to_string_lossy()) {
+                Ok(texture) => {
+                    material.diffuse_texture = Some(Rc::new(texture));
+                    continue;
+                }
+                Err(source) => MeshLoadError::Texture {
+                    file: path.clone(),
+                    source: Box::new(source),
+                },
+            };
+            // textures that aren't there leave the material untextured, only broken ones fail
+            if path.is_file() {
+                diagnostics.report(error)?;
+            } else {
+                diagnostics.warn(error);
+            }
+        }
+
+        Ok(materials)
+    }
+
+    /// The diffuse color, with the dissolve as its alpha.
+    pub fn diffuse_color(&self) -> color::RGBA {
+        rgba(&self.diffuse, self.dissolve)
+    }
+
+    /// The specular part of the material for the lit shaders.
+    pub fn lighting_material(&self) -> Material {
+        let specular = if self.illumination_model < 2 {
+            Vec3::new(0.0, 0.0, 0.0)
+        } else {
+            self.specular
+        };
+
+        Material {
+            specular: rgba(&specular, 1.0),
+            shininess: self.shininess,
+        }
+    }
+}
+
+/// A run of consecutive faces drawn with one material, from a `usemtl` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaterialRange {
+    /// Index into `Mesh::materials`.
+    pub material: usize,
+    pub faces: Range<usize>,
+}
+
+impl Mesh {
+    /// The index into `materials` of every face's material, or `None` for faces no range covers.
+    pub fn face_materials(&self) -> Vec<Option<usize>> {
+        let mut face_materials = vec![None; self.faces.len()];
+        for range in &self.material_ranges {
+            let faces =
+                range.faces.start.min(self.faces.len())..range.faces.end.min(self.faces.len());
+            face_materials[faces].fill(Some(range.material));
+        }
+
+        face_materials
+    }
+
+    /// Starts a material range at the next face for a `usemtl` statement, after ending `current`.
+    /// Names that aren't in `materials` leave the following faces without a material.
+    pub(super) fn use_material(
+        &mut self,
+        name: &str,
+        current: Option<MaterialRange>,
+    ) -> Option<MaterialRange> {
+        self.end_material_range(current);
+
+        let material = self
+            .materials
+            .iter()
+            .position(|material| material.name == name)?;
+        let start = self.faces.len();
+        Some(MaterialRange {
+            material,
+            faces: start..start,
+        })
+    }
+
+    /// Ends a material range after the last face read so far. Ranges without faces are dropped.
+    pub(super) fn end_material_range(&mut self, range: Option<MaterialRange>) {
+        if let Some(mut range) = range {
+            range.faces.end = self.faces.len();
+            if !range.faces.is_empty() {
+                self.material_ranges.push(range);
+            }
+        }
+    }
+}
+
+fn rgba(color: &Vec3<f32>, alpha: f32) -> color::RGBA {
+    let channel = |value: f32| (value * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    color::RGBA {
+        r: channel(color.x),
+        g: channel(color.y),
+        b: channel(color.z),
+        a: channel(alpha),
+    }
+}
+
+/// Applies one line of an MTL file to the material it belongs to, or starts a new material.
+fn parse_statement(
+    line: &str,
+    directory: &Path,
+    materials: &mut Vec<MtlMaterial>,
+) -> Result<(), LineError> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let Some((&keyword, arguments)) = tokens.split_first() else {
+        return Ok(());
+    };
+
+    if keyword == "newmtl" {
+        materials.push(MtlMaterial::new(&arguments.join(" ")));
+        return Ok(());
+    }
+    if keyword.starts_with('#') {
+        return Ok(());
+    }
+
+    let material = materials
+        .last_mut()
+        .ok_or_else(|| LineError::at(line, keyword, "statement before newmtl".to_string()))?;
+    match keyword {
+        "Ka" => material.ambient = parse_color(line, arguments)?,
+        "Kd" => material.diffuse = parse_color(line, arguments)?,
+        "Ks" => material.specular = parse_color(line, arguments)?,
+        "Ns" => material.shininess = parse_number(line, arguments)?,
+        "d" => material.dissolve = parse_number(line, arguments)?,
+        "Tr" => material.dissolve = 1.0 - parse_number::<f32>(line, arguments)?,
+        "illum" => material.illumination_model = parse_number(line, arguments)?,
+        "map_Kd" => material.diffuse_map = map_path(line, directory, arguments)?,
+        "map_Bump" | "map_bump" | "bump" => {
+            material.bump_map = map_path(line, directory, arguments)?
+        }
+        "map_Ks" => material.specular_map = map_path(line, directory, arguments)?,
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Parses the first of `arguments`, which are slices of `line`.
+fn parse_number<N: FromStr>(line: &str, arguments: &[&str]) -> Result<N, LineError> {
+    let token = arguments
+        .first()
+        .ok_or_else(|| LineError::missing(line, "material value"))?;
+    token
+        .parse()
+        .map_err(|_| LineError::at(line, token, format!("invalid number \"{}\"", token)))
+}
+
+/// Reads an RGB color. A single value is used for all three channels.
+fn parse_color(line: &str, arguments: &[&str]) -> Result<Vec3<f32>, LineError> {
+    let r: f32 = parse_number(line, arguments)?;
+    match arguments.len() {
+        1 => Ok(Vec3::new(r, r, r)),
+        _ => Ok(Vec3::new(
+            r,
+            parse_number(line, &arguments[1..])?,
+            parse_number(line, &arguments[2..])?,
+        )),
+    }
+}
+
+/// Whether a texture map names a TGA image, the only kind of texture that can be read.
+fn is_tga(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("tga"))
+}
+
+/// The file a texture map statement names, after any options like `-bm 1.0`, relative to the MTL
+/// file. The file name is everything after the options, so it may contain spaces.
+fn map_path(
+    line: &str,
+    directory: &Path,
+    arguments: &[&str],
+) -> Result<Option<PathBuf>, LineError> {
+    let mut file_name = arguments;
+    while let Some((option, rest)) = file_name.split_first() {
+        if !option.starts_with('-') {
+            break;
+        }
+        file_name = &rest[option_length(option, rest)..];
+    }
+
+    if file_name.is_empty() {
+        return Err(LineError::missing(line, "texture file"));
+    }
+    Ok(Some(directory.join(file_name.join(" "))))
+}
+
+/// How many of the `arguments` after a texture map option belong to it. `-o`, `-s` and `-t` take
+/// one to three numbers, `-mm` takes two and the rest take one.
+fn option_length(option: &str, arguments: &[&str]) -> usize {
+    let length = match option {
+        "-o" | "-s" | "-t" => arguments
+            .iter()
+            .take(3)
+            .take_while(|argument| argument.parse::<f32>().is_ok())
+            .count(),
+        "-mm" => 2,
+        _ => 1,
+    };
+    length.min(arguments.len())
+}
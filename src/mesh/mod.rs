@@ -0,0 +1,208 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use crate::geometry::{Vec3, Vec4};
+
+pub mod error;
+pub mod flat;
+pub mod group;
+pub mod material;
+pub mod normals;
+pub mod obj;
+pub mod options;
+pub mod triangulate;
+
+use error::{Diagnostics, LineError};
+use obj::{LineReader, Statement};
+pub use error::MeshLoadError;
+pub use flat::{FlatMesh, NO_INDEX};
+pub use group::{FaceGroup, GroupKind};
+pub use material::{MaterialRange, MtlMaterial};
+pub use normals::NormalWeighting;
+pub use options::{ObjLoadOptions, ParseMode};
+
+#[derive(Debug, Clone)]
+pub struct FaceElement {
+    pub vertex_index: Option<i32>,
+    pub texture_index: Option<i32>,
+    pub normal_index: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mesh {
+    pub vertices: Vec<Vec4<f32>>,
+    pub vertex_normals: Vec<Vec3<f32>>,
+    pub texture_coordinates: Vec<Vec3<f32>>,
+    pub faces: Vec<Vec<FaceElement>>,
+    /// The materials of the OBJ file's `mtllib` libraries.
+    pub materials: Vec<MtlMaterial>,
+    /// Which faces each `usemtl` statement covers, in file order.
+    pub material_ranges: Vec<MaterialRange>,
+    /// The named sub-meshes from `o` and `g` statements.
+    pub groups: Vec<FaceGroup>,
+    /// The `s` smoothing group of every face, 0 for none. Empty when the file never sets one, in
+    /// which case every face is smoothed together.
+    pub smoothing_groups: Vec<u32>,
+}
+
+impl Default for Mesh {
+    fn default() -> Mesh {
+        Mesh::new()
+    }
+}
+
+impl Mesh {
+    pub fn new() -> Mesh {
+        Mesh {
+            vertices: Vec::new(),
+            vertex_normals: Vec::new(),
+            texture_coordinates: Vec::new(),
+            faces: Vec::new(),
+            materials: Vec::new(),
+            material_ranges: Vec::new(),
+            groups: Vec::new(),
+            smoothing_groups: Vec::new(),
+        }
+    }
+
+    /// Reads the vertices, texture coordinates, normals and faces of an OBJ file with the default
+    /// `ObjLoadOptions`, which split faces into triangles and fail on the first problem. Use
+    /// `load_obj` to get the warnings or to skip whatever can't be parsed.
+    pub fn from_obj_file(obj_file_path: &str) -> Result<Mesh, MeshLoadError> {
+        let (mesh, _) = Mesh::load_obj(obj_file_path, &ObjLoadOptions::default())?;
+        Ok(mesh)
+    }
+
+    /// Reads an OBJ file, returning the mesh and, in lenient mode, the problems that were
+    /// skipped. Material libraries named by `mtllib` are read from next to the OBJ file, and
+    /// faces using materials that couldn't be found are drawn like faces without one. Objects and
+    /// groups become face ranges in `groups`, and `s` statements set `smoothing_groups`.
+    pub fn load_obj(
+        obj_file_path: &str,
+        options: &ObjLoadOptions,
+    ) -> Result<(Mesh, Vec<MeshLoadError>), MeshLoadError> {
+        let file = PathBuf::from(obj_file_path);
+        let reader = File::open(&file).map_err(|source| MeshLoadError::Io {
+            file: file.clone(),
+            source,
+        })?;
+        let directory = file.parent().unwrap_or(Path::new(""));
+        let mut diagnostics = Diagnostics {
+            strict: options.mode == ParseMode::Strict,
+            warnings: Vec::new(),
+        };
+
+        let mut mesh = Mesh::new();
+        let mut material_range = None;
+        let mut objects = Vec::new();
+        let mut groups = Vec::new();
+        let mut smoothing_group = None;
+        let mut corners = Vec::new();
+
+        let mut lines = LineReader::new(BufReader::new(reader));
+        while let Some((line_number, line)) =
+            lines.next_line().map_err(|source| MeshLoadError::Io {
+                file: file.clone(),
+                source,
+            })?
+        {
+            let counts = [
+                mesh.vertices.len(),
+                mesh.texture_coordinates.len(),
+                mesh.vertex_normals.len(),
+            ];
+
+            let statement = match obj::parse_line(line, counts, &mut corners) {
+                Ok(statement) => statement,
+                Err(error) => {
+                    diagnostics.report(error.into_load_error(&file, line_number, line))?;
+                    continue;
+                }
+            };
+
+            match statement {
+                Statement::Vertex(vertex) => mesh.vertices.push(vertex),
+                Statement::TextureCoordinate(coordinate) => {
+                    mesh.texture_coordinates.push(coordinate)
+                }
+                Statement::VertexNormal(normal) => mesh.vertex_normals.push(normal),
+                Statement::Face => {
+                    let face = corners.iter().map(|&corner| obj::face_element(corner)).collect();
+                    if options.triangulate {
+                        mesh.push_triangulated(face);
+                    } else {
+                        mesh.faces.push(face);
+                    }
+                    mesh.smoothing_groups.resize(mesh.faces.len(), smoothing_group.unwrap_or(0));
+                }
+                Statement::Other { keyword: "mtllib", arguments } => {
+                    for file_name in arguments.split_whitespace() {
+                        let mtl_file_path = directory.join(file_name);
+                        match MtlMaterial::read_mtl(&mtl_file_path, &mut diagnostics) {
+                            Ok(materials) => mesh.materials.extend(materials),
+                            // exporters often name libraries that weren't shipped with the OBJ
+                            Err(error @ MeshLoadError::Io { .. }) if !mtl_file_path.is_file() => {
+                                diagnostics.warn(error)
+                            }
+                            Err(error) => diagnostics.report(error)?,
+                        }
+                    }
+                }
+                Statement::Other { keyword: "usemtl", arguments: name } => {
+                    let is_known = mesh.materials.iter().any(|material| material.name == name);
+                    material_range = mesh.use_material(name, material_range);
+
+                    if !is_known {
+                        let error =
+                            LineError::at(line, name, format!("unknown material \"{}\"", name));
+                        diagnostics.warn(error.into_load_error(&file, line_number, line));
+                    }
+                }
+                Statement::Other { keyword: "o", arguments } => {
+                    let names: Vec<&str> = arguments.split_whitespace().collect();
+                    objects = mesh.start_groups(GroupKind::Object, &names, objects);
+                }
+                Statement::Other { keyword: "g", arguments } => {
+                    let names: Vec<&str> = arguments.split_whitespace().collect();
+                    groups = mesh.start_groups(GroupKind::Group, &names, groups);
+                }
+                Statement::Other { keyword: "s", .. } => match Mesh::parse_smoothing_group(line) {
+                    Ok(group) => smoothing_group = Some(group),
+                    Err(error) => {
+                        diagnostics.report(error.into_load_error(&file, line_number, line))?
+                    }
+                },
+                Statement::Other { .. } => {}
+            }
+        }
+        mesh.end_material_range(material_range);
+        mesh.end_groups(groups);
+        mesh.end_groups(objects);
+        if smoothing_group.is_none() {
+            mesh.smoothing_groups.clear();
+        }
+
+        Ok((mesh, diagnostics.warnings))
+    }
+
+    /// The sum of the cross products of a face's edges, whose length is twice its area.
+    fn newell_normal(&self, face: &[FaceElement]) -> Vec3<f32> {
+        let positions: Vec<Vec3<f32>> = face
+            .iter()
+            .filter_map(|element| self.position(element))
+            .collect();
+
+        triangulate::newell_normal(&positions)
+    }
+
+    fn vertex_index(&self, element: &FaceElement) -> Option<usize> {
+        let index: usize = element.vertex_index?.try_into().ok()?;
+        (index < self.vertices.len()).then_some(index)
+    }
+
+    fn position(&self, element: &FaceElement) -> Option<Vec3<f32>> {
+        let vertex = self.vertices[self.vertex_index(element)?];
+        Some(Vec3::new(vertex.x, vertex.y, vertex.z))
+    }
+}
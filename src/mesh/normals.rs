@@ -0,0 +1,149 @@
+use crate::geometry::Vec3;
+use crate::mesh::{FaceElement, Mesh};
+
+/// How much each face around a vertex counts towards its smooth normal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// By the face's area, so large faces dominate.
+    Area,
+    /// By the face's angle at the vertex, which doesn't change when a face is split into smaller
+    /// triangles.
+    Angle,
+}
+
+impl Mesh {
+    /// The unit normal of every face, following its counter clockwise winding. Faces of more than
+    /// three vertices use Newell's method, so concave and slightly non-planar faces still get a
+    /// sensible normal. Degenerate faces get the zero vector.
+    pub fn face_normals(&self) -> Vec<Vec3<f32>> {
+        self.faces
+            .iter()
+            .map(|face| self.newell_normal(face).normalized())
+            .collect()
+    }
+
+    /// Replaces `vertex_normals` with smooth normals and points every face element's
+    /// `normal_index` at them, so lighting works on meshes loaded without `vn` lines.
+    ///
+    /// Each corner's normal is the weighted sum of the normals of the faces sharing its vertex and
+    /// a smoothing group with the corner's face. With a `crease_angle` in radians, faces meeting
+    /// the corner's face at a sharper angle are left out too, which splits the vertex along hard
+    /// edges. A crease angle of 0 gives faceted normals. Corners without a valid vertex index are
+    /// left without a normal.
+    pub fn generate_normals(&mut self, weighting: NormalWeighting, crease_angle: Option<f32>) {
+        let face_normals = self.face_normals();
+        let face_areas: Vec<f32> = self
+            .faces
+            .iter()
+            .map(|face| self.newell_normal(face).length() / 2.0)
+            .collect();
+
+        // the corners of every face that touch each vertex, as (face, corner) pairs
+        let mut incident: Vec<Vec<(usize, usize)>> = vec![Vec::new(); self.vertices.len()];
+        for (f, face) in self.faces.iter().enumerate() {
+            for (c, element) in face.iter().enumerate() {
+                if let Some(vertex) = self.vertex_index(element) {
+                    incident[vertex].push((f, c));
+                }
+            }
+        }
+
+        let min_cos = crease_angle.map(f32::cos);
+        let weight = |face: usize, corner: usize| match weighting {
+            NormalWeighting::Area => face_areas[face],
+            NormalWeighting::Angle => self.corner_angle(&self.faces[face], corner),
+        };
+        // without creases or smoothing groups every corner of a vertex gets the same normal
+        let smooths_everywhere =
+            min_cos.is_none() && self.smoothing_groups.len() != self.faces.len();
+
+        let mut vertex_normals: Vec<Vec3<f32>> = Vec::new();
+        let mut normal_indices: Vec<Vec<Option<i32>>> = self
+            .faces
+            .iter()
+            .map(|face| vec![None; face.len()])
+            .collect();
+
+        for corners in &incident {
+            if smooths_everywhere {
+                if corners.is_empty() {
+                    continue;
+                }
+
+                let mut sum = Vec3::new(0.0, 0.0, 0.0);
+                for &(g, d) in corners {
+                    sum = &sum + &face_normals[g].scaled(weight(g, d));
+                }
+                vertex_normals.push(sum.normalized());
+
+                let index = Some(vertex_normals.len() as i32 - 1);
+                for &(f, c) in corners {
+                    normal_indices[f][c] = index;
+                }
+                continue;
+            }
+
+            // the normals already made for this vertex, so corners that agree share one
+            let first_normal = vertex_normals.len();
+
+            for &(f, c) in corners {
+                let mut sum = Vec3::new(0.0, 0.0, 0.0);
+
+                for &(g, d) in corners {
+                    if !self.is_smoothed_with(f, g) {
+                        continue;
+                    }
+
+                    let is_creased = min_cos.is_some_and(|min_cos| {
+                        face_normals[f].length() > 0.0
+                            && &face_normals[f] * &face_normals[g] < min_cos
+                    });
+                    if is_creased {
+                        continue;
+                    }
+
+                    sum = &sum + &face_normals[g].scaled(weight(g, d));
+                }
+
+                let normal = sum.normalized();
+                let index = match vertex_normals[first_normal..]
+                    .iter()
+                    .position(|existing| *existing == normal)
+                {
+                    Some(offset) => first_normal + offset,
+                    None => {
+                        vertex_normals.push(normal);
+                        vertex_normals.len() - 1
+                    }
+                };
+                normal_indices[f][c] = Some(index as i32);
+            }
+        }
+
+        for (face, indices) in self.faces.iter_mut().zip(normal_indices) {
+            for (element, normal_index) in face.iter_mut().zip(indices) {
+                element.normal_index = normal_index;
+            }
+        }
+        self.vertex_normals = vertex_normals;
+    }
+
+    /// The interior angle of a face at one of its corners, in radians.
+    fn corner_angle(&self, face: &[FaceElement], corner: usize) -> f32 {
+        let previous = &face[(corner + face.len() - 1) % face.len()];
+        let next = &face[(corner + 1) % face.len()];
+
+        match (
+            self.position(previous),
+            self.position(&face[corner]),
+            self.position(next),
+        ) {
+            (Some(previous), Some(current), Some(next)) => {
+                let a = (&previous - &current).normalized();
+                let b = (&next - &current).normalized();
+                (&a * &b).clamp(-1.0, 1.0).acos()
+            }
+            _ => 0.0,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::{self, BufRead};
+use std::str::{FromStr, SplitAsciiWhitespace};
+
+use crate::geometry::{Vec3, Vec4};
+use crate::mesh::error::LineError;
+use crate::mesh::{FaceElement, NO_INDEX};
+
+/// Reads a file one line at a time into a buffer that is reused, so that reading only allocates
+/// when a line is longer than any before it.
+pub(super) struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> LineReader<R> {
+        LineReader {
+            reader,
+            buffer: String::new(),
+            line_number: 0,
+        }
+    }
+
+    /// The next line without its line ending, and its 1-based number, or `None` at the end.
+    pub fn next_line(&mut self) -> io::Result<Option<(usize, &str)>> {
+        self.buffer.clear();
+        if self.reader.read_line(&mut self.buffer)? == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+
+        Ok(Some((
+            self.line_number,
+            self.buffer.trim_end_matches(['\n', '\r']),
+        )))
+    }
+}
+
+/// A line of an OBJ file, as far as the loaders share its parsing.
+pub(super) enum Statement<'a> {
+    Vertex(Vec4<f32>),
+    TextureCoordinate(Vec3<f32>),
+    VertexNormal(Vec3<f32>),
+    /// A face of at least three corners, which were read into the buffer given to `parse_line`.
+    Face,
+    /// Any other line. `keyword` is its first token, empty for blank lines, and `arguments` is
+    /// the trimmed rest of the line.
+    Other {
+        keyword: &'a str,
+        arguments: &'a str,
+    },
+}
+
+/// Parses a line of an OBJ file, given how many vertices, texture coordinates and normals have
+/// been read so far. Face corners are checked against those counts and stored in `corners` as
+/// 0-based vertex, texture coordinate and normal indices, with `NO_INDEX` where a corner has
+/// none.
+pub(super) fn parse_line<'a>(
+    line: &'a str,
+    counts: [usize; 3],
+    corners: &mut Vec<[u32; 3]>,
+) -> Result<Statement<'a>, LineError> {
+    let mut tokens = line.split_ascii_whitespace();
+    let Some(keyword) = tokens.next() else {
+        return Ok(Statement::Other {
+            keyword: "",
+            arguments: "",
+        });
+    };
+
+    let statement = match keyword {
+        "v" => {
+            let x = next_number(line, &mut tokens, "vertex x")?;
+            let y = next_number(line, &mut tokens, "vertex y")?;
+            let z = next_number(line, &mut tokens, "vertex z")?;
+            let w = optional_number(line, &mut tokens, "vertex w")?.unwrap_or(1.0);
+            Statement::Vertex(Vec4 { x, y, z, w })
+        }
+        "vt" => {
+            let u = next_number(line, &mut tokens, "texture coordinate u")?;
+            let v = optional_number(line, &mut tokens, "texture coordinate v")?;
+            let w = optional_number(line, &mut tokens, "texture coordinate w")?;
+            Statement::TextureCoordinate(Vec3::new(u, v.unwrap_or(0.0), w.unwrap_or(0.0)))
+        }
+        "vn" => {
+            let x = next_number(line, &mut tokens, "normal x")?;
+            let y = next_number(line, &mut tokens, "normal y")?;
+            let z = next_number(line, &mut tokens, "normal z")?;
+            Statement::VertexNormal(Vec3 { x, y, z })
+        }
+        "f" => {
+            parse_face(line, tokens, counts, corners)?;
+            Statement::Face
+        }
+        _ => {
+            let end = keyword.as_ptr() as usize - line.as_ptr() as usize + keyword.len();
+            Statement::Other {
+                keyword,
+                arguments: line[end..].trim(),
+            }
+        }
+    };
+
+    Ok(statement)
+}
+
+/// The `FaceElement` of a corner read by `parse_line`.
+pub(super) fn face_element(corner: [u32; 3]) -> FaceElement {
+    let index = |index: u32| (index != NO_INDEX).then_some(index as i32);
+
+    FaceElement {
+        vertex_index: index(corner[0]),
+        texture_index: index(corner[1]),
+        normal_index: index(corner[2]),
+    }
+}
+
+/// Reads the corners of a face into `corners`. Every index must point at something already read,
+/// and there must be at least three corners.
+fn parse_face(
+    line: &str,
+    tokens: SplitAsciiWhitespace,
+    counts: [usize; 3],
+    corners: &mut Vec<[u32; 3]>,
+) -> Result<(), LineError> {
+    corners.clear();
+    for token in tokens {
+        if token.starts_with('#') {
+            break;
+        }
+
+        // OBJ format supports: v, v/vt, v/vt/vn, v//vn
+        let index_count = token.split('/').count();
+        if index_count > 3 {
+            let message = format!("expected 1-3 indices, got {}", index_count);
+            return Err(LineError::at(line, token, message));
+        }
+
+        let mut corner = [NO_INDEX; 3];
+        for (i, index) in token.split('/').enumerate() {
+            corner[i] = resolve_index(line, index, counts[i], i)?;
+        }
+        corners.push(corner);
+    }
+
+    if corners.len() < 3 {
+        return Err(LineError::missing(
+            line,
+            "face corners, a face needs at least 3",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Turns the `kind`th index of a face corner, 0 for vertex, 1 for texture coordinate and 2 for
+/// normal, into a 0-based one, given how many of that kind have been read. Negative indices count
+/// back from the last one read. Only the vertex index can't be empty.
+fn resolve_index(line: &str, index: &str, count: usize, kind: usize) -> Result<u32, LineError> {
+    let resolved = match index.parse::<i64>() {
+        Ok(index) if index > 0 && index as u64 <= count as u64 => Some(index as u32 - 1),
+        Ok(index) if index < 0 && index.unsigned_abs() <= count as u64 => {
+            Some((count as i64 + index) as u32)
+        }
+        Err(_) if index.is_empty() && kind != 0 => Some(NO_INDEX),
+        _ => None,
+    };
+
+    resolved.ok_or_else(|| {
+        let kind = ["vertex", "texture coordinate", "normal"][kind];
+        LineError::at(line, index, format!("invalid {} index \"{}\"", kind, index))
+    })
+}
+
+fn next_number<T: FromStr>(
+    line: &str,
+    tokens: &mut SplitAsciiWhitespace,
+    what: &str,
+) -> Result<T, LineError> {
+    optional_number(line, tokens, what)?.ok_or_else(|| LineError::missing(line, what))
+}
+
+fn optional_number<T: FromStr>(
+    line: &str,
+    tokens: &mut SplitAsciiWhitespace,
+    what: &str,
+) -> Result<Option<T>, LineError> {
+    tokens
+        .next()
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|_| LineError::at(line, token, format!("invalid {} \"{}\"", what, token)))
+        })
+        .transpose()
+}
@@ -0,0 +1,43 @@
+/// What the OBJ and MTL loaders do about lines they can't parse, faces pointing at vertices that
+/// don't exist and files they can't read. Material libraries, materials and textures that aren't
+/// there are only ever warnings, in either mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Gives up with the first problem as the error.
+    Strict,
+    /// Skips whatever has a problem and carries on, returning the problems as warnings.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjLoadOptions {
+    pub mode: ParseMode,
+    /// Whether faces of more than three corners are split into triangles as they are read.
+    pub triangulate: bool,
+}
+
+impl Default for ObjLoadOptions {
+    /// Strict, triangulating faces.
+    fn default() -> ObjLoadOptions {
+        ObjLoadOptions::new()
+    }
+}
+
+impl ObjLoadOptions {
+    pub fn new() -> ObjLoadOptions {
+        ObjLoadOptions {
+            mode: ParseMode::Strict,
+            triangulate: true,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: ParseMode) -> ObjLoadOptions {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_triangulate(mut self, triangulate: bool) -> ObjLoadOptions {
+        self.triangulate = triangulate;
+        self
+    }
+}
@@ -0,0 +1,178 @@
+use std::ops::Range;
+
+use crate::geometry::Vec3;
+use crate::mesh::{FaceElement, Mesh};
+
+impl Mesh {
+    /// Splits every face of more than three corners into triangles, keeping their winding, and
+    /// drops faces of fewer than three. Material ranges, groups and smoothing groups are moved to
+    /// the triangles of the faces they covered. Meshes read with `from_obj_file` are already
+    /// triangulated.
+    pub fn triangulate(&mut self) {
+        let faces = std::mem::take(&mut self.faces);
+        let face_count = faces.len();
+
+        // the index of each face's first triangle, plus one past the last triangle
+        let mut first_triangles = Vec::with_capacity(face_count + 1);
+        for face in faces {
+            first_triangles.push(self.faces.len());
+            self.push_triangulated(face);
+        }
+        first_triangles.push(self.faces.len());
+
+        let remap = |faces: &Range<usize>| {
+            first_triangles[faces.start.min(face_count)]..first_triangles[faces.end.min(face_count)]
+        };
+        for range in &mut self.material_ranges {
+            range.faces = remap(&range.faces);
+        }
+        for group in &mut self.groups {
+            group.faces = remap(&group.faces);
+        }
+
+        if self.smoothing_groups.len() == face_count {
+            self.smoothing_groups = (0..face_count)
+                .flat_map(|face| {
+                    let triangle_count = first_triangles[face + 1] - first_triangles[face];
+                    std::iter::repeat_n(self.smoothing_groups[face], triangle_count)
+                })
+                .collect();
+        }
+    }
+
+    /// Appends the triangles `face` splits into. Convex faces become a fan around their first
+    /// corner, and concave ones are split by ear clipping. Faces whose corners can't all be
+    /// looked up yet are fanned.
+    pub(super) fn push_triangulated(&mut self, face: Vec<FaceElement>) {
+        if face.len() == 3 {
+            self.faces.push(face);
+            return;
+        }
+
+        for [a, b, c] in self.triangle_corners(&face) {
+            self.faces
+                .push(vec![face[a].clone(), face[b].clone(), face[c].clone()]);
+        }
+    }
+
+    fn triangle_corners(&self, face: &[FaceElement]) -> Vec<[usize; 3]> {
+        if face.len() < 3 {
+            return Vec::new();
+        }
+
+        let positions: Option<Vec<Vec3<f32>>> =
+            face.iter().map(|element| self.position(element)).collect();
+        match positions {
+            Some(positions) => polygon_triangles(&positions),
+            None => fan(face.len()),
+        }
+    }
+}
+
+/// The corners of the triangles a polygon of at least three corners splits into, fanned when it
+/// is convex or has no area and ear clipped otherwise.
+pub(super) fn polygon_triangles(positions: &[Vec3<f32>]) -> Vec<[usize; 3]> {
+    let normal = newell_normal(positions);
+    if normal.length() == 0.0 {
+        return fan(positions.len());
+    }
+
+    let points = project(positions, &normal);
+    if is_convex(&points) {
+        fan(positions.len())
+    } else {
+        clip_ears(&points)
+    }
+}
+
+/// The sum of the cross products of a polygon's edges, whose length is twice its area.
+pub(super) fn newell_normal(positions: &[Vec3<f32>]) -> Vec3<f32> {
+    let mut normal = Vec3::new(0.0, 0.0, 0.0);
+    for (i, current) in positions.iter().enumerate() {
+        let next = &positions[(i + 1) % positions.len()];
+        normal = &normal + &current.cross(next);
+    }
+
+    normal
+}
+
+fn fan(corner_count: usize) -> Vec<[usize; 3]> {
+    (1..corner_count - 1).map(|i| [0, i, i + 1]).collect()
+}
+
+/// Flattens a polygon onto the coordinate plane its normal is closest to facing, mirrored if
+/// needed so that the 2D polygon winds counter clockwise.
+fn project(positions: &[Vec3<f32>], normal: &Vec3<f32>) -> Vec<(f32, f32)> {
+    let (ax, ay, az) = (normal.x.abs(), normal.y.abs(), normal.z.abs());
+
+    positions
+        .iter()
+        .map(|p| {
+            if az >= ax && az >= ay {
+                (p.x * normal.z.signum(), p.y)
+            } else if ax >= ay {
+                (p.y * normal.x.signum(), p.z)
+            } else {
+                (p.z * normal.y.signum(), p.x)
+            }
+        })
+        .collect()
+}
+
+/// Twice the signed area of the triangle, positive when `a`, `b`, `c` turn counter clockwise.
+fn cross(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn is_convex(points: &[(f32, f32)]) -> bool {
+    let n = points.len();
+    (0..n).all(|i| cross(points[i], points[(i + 1) % n], points[(i + 2) % n]) >= 0.0)
+}
+
+/// Triangulates a simple counter clockwise polygon by repeatedly cutting off a corner whose
+/// triangle holds no other corner. If no such ear is left, which only happens for degenerate or
+/// self-intersecting polygons, the rest is fanned.
+fn clip_ears(points: &[(f32, f32)]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            if cross(points[a], points[b], points[c]) <= 0.0 {
+                return false;
+            }
+
+            remaining
+                .iter()
+                .filter(|&&other| other != a && other != b && other != c)
+                .all(|&other| !is_in_triangle(points[other], points[a], points[b], points[c]))
+        });
+
+        let Some(i) = ear else {
+            break;
+        };
+        triangles.push([
+            remaining[(i + n - 1) % n],
+            remaining[i],
+            remaining[(i + 1) % n],
+        ]);
+        remaining.remove(i);
+    }
+
+    for i in 1..remaining.len() - 1 {
+        triangles.push([remaining[0], remaining[i], remaining[i + 1]]);
+    }
+
+    triangles
+}
+
+/// Whether `p` is inside or on the edge of the counter clockwise triangle `a`, `b`, `c`.
+fn is_in_triangle(p: (f32, f32), a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
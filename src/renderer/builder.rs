@@ -1,11 +1,19 @@
 use crate::algorithms::Algorithms;
 use crate::graphics::{RenderTarget, TinyRendererWindow, color};
-use crate::renderer::TinyRenderer;
+use crate::renderer::{Camera, CameraControls, TinyRenderer};
 
 pub struct TinyRendererBuilder<T: RenderTarget> {
     render_output: Option<T>,
     algorithms: Option<Algorithms<T>>,
     color: Option<color::RGBA>,
+    camera: Option<Camera>,
+    camera_controls: Option<CameraControls>,
+}
+
+impl<T: RenderTarget> Default for TinyRendererBuilder<T> {
+    fn default() -> TinyRendererBuilder<T> {
+        TinyRendererBuilder::new()
+    }
 }
 
 impl<T: RenderTarget> TinyRendererBuilder<T> {
@@ -14,6 +22,8 @@ impl<T: RenderTarget> TinyRendererBuilder<T> {
             render_output: None,
             algorithms: None,
             color: None,
+            camera: None,
+            camera_controls: None,
         }
     }
 
@@ -32,6 +42,11 @@ impl<T: RenderTarget> TinyRendererBuilder<T> {
         self
     }
 
+    pub fn with_camera(mut self, camera: Camera) -> TinyRendererBuilder<T> {
+        self.camera = Some(camera);
+        self
+    }
+
     pub fn build(self) -> TinyRenderer<T> {
         let render_output = self.render_output.unwrap_or_else(|| {
             panic!("Cannot create a renderer with no output renderer.");
@@ -49,7 +64,15 @@ impl<T: RenderTarget> TinyRendererBuilder<T> {
             },
         };
 
-        TinyRenderer::new(render_output, algorithms, color)
+        let mut renderer = TinyRenderer::new(render_output, algorithms, color);
+        if let Some(camera) = self.camera {
+            renderer.set_camera(camera);
+        }
+        if let Some(camera_controls) = self.camera_controls {
+            renderer.set_camera_controls(camera_controls);
+        }
+
+        renderer
     }
 }
 
@@ -62,4 +85,12 @@ impl TinyRendererBuilder<TinyRendererWindow> {
 
         self
     }
+
+    pub fn with_camera_controls(
+        mut self,
+        camera_controls: CameraControls,
+    ) -> TinyRendererBuilder<TinyRendererWindow> {
+        self.camera_controls = Some(camera_controls);
+        self
+    }
 }
@@ -0,0 +1,163 @@
+use std::f32::consts::FRAC_PI_2;
+
+use crate::geometry::{Mat4, Vec3};
+
+// keeps orbiting and looking from flipping over the poles
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    /// `fov_y` is the vertical field of view in radians.
+    Perspective { fov_y: f32 },
+    /// `height` is the height of the visible area in world units.
+    Orthographic { height: f32 },
+}
+
+/// The point of view the scene is rendered from. The up vector is expected to be close to +y for
+/// the orbit and look controls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub position: Vec3<f32>,
+    pub target: Vec3<f32>,
+    pub up: Vec3<f32>,
+    pub projection: Projection,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Default for Camera {
+    /// An orthographic camera looking down -z at the [-1, 1] cube, so untransformed meshes fill
+    /// the whole target.
+    fn default() -> Camera {
+        Camera::orthographic(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 0.0), 2.0)
+            .with_clip_planes(0.0, 2.0)
+    }
+}
+
+impl Camera {
+    pub fn perspective(position: Vec3<f32>, target: Vec3<f32>, fov_y: f32) -> Camera {
+        Camera {
+            position,
+            target,
+            up: Vec3::new(0.0, 1.0, 0.0),
+            projection: Projection::Perspective { fov_y },
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+
+    pub fn orthographic(position: Vec3<f32>, target: Vec3<f32>, height: f32) -> Camera {
+        Camera {
+            position,
+            target,
+            up: Vec3::new(0.0, 1.0, 0.0),
+            projection: Projection::Orthographic { height },
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+
+    pub fn with_clip_planes(mut self, near: f32, far: f32) -> Camera {
+        self.near = near;
+        self.far = far;
+        self
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at(&self.position, &self.target, &self.up)
+    }
+
+    /// `aspect` is the width of the render target divided by its height.
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        match self.projection {
+            Projection::Perspective { fov_y } => {
+                Mat4::perspective(fov_y, aspect, self.near, self.far)
+            }
+            Projection::Orthographic { height } => {
+                let half_height = height / 2.0;
+                let half_width = half_height * aspect;
+                Mat4::orthographic(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    self.near,
+                    self.far,
+                )
+            }
+        }
+    }
+
+    pub fn forward(&self) -> Vec3<f32> {
+        (&self.target - &self.position).normalized()
+    }
+
+    pub fn right(&self) -> Vec3<f32> {
+        self.forward().cross(&self.up).normalized()
+    }
+
+    /// Moves the camera around its target by `yaw` and `pitch` radians, keeping the distance.
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        let offset = &self.position - &self.target;
+        let (radius, current_yaw, current_pitch) = to_spherical(&offset);
+
+        let direction = from_spherical(current_yaw + yaw, current_pitch + pitch);
+        self.position = &self.target + &direction.scaled(radius);
+    }
+
+    /// Turns the camera in place by `yaw` and `pitch` radians, moving the target instead.
+    pub fn look(&mut self, yaw: f32, pitch: f32) {
+        let offset = &self.target - &self.position;
+        let (distance, current_yaw, current_pitch) = to_spherical(&offset);
+
+        let direction = from_spherical(current_yaw + yaw, current_pitch + pitch);
+        self.target = &self.position + &direction.scaled(distance);
+    }
+
+    /// Scales the distance to the target by `factor`, or the visible height for orthographic
+    /// cameras. Factors below 1 zoom in.
+    pub fn zoom(&mut self, factor: f32) {
+        match &mut self.projection {
+            Projection::Perspective { .. } => {
+                let offset = &self.position - &self.target;
+                let distance = (offset.length() * factor).max(self.near);
+                self.position = &self.target + &offset.normalized().scaled(distance);
+            }
+            Projection::Orthographic { height } => {
+                *height = (*height * factor).max(f32::EPSILON);
+            }
+        }
+    }
+
+    /// Moves both the camera and its target, relative to the way the camera is facing.
+    pub fn fly(&mut self, forward: f32, right: f32, up: f32) {
+        let movement = &(&self.forward().scaled(forward) + &self.right().scaled(right))
+            + &self.up.normalized().scaled(up);
+
+        self.position = &self.position + &movement;
+        self.target = &self.target + &movement;
+    }
+}
+
+/// Returns (length, yaw around +y, pitch from the xz plane) of `v`.
+fn to_spherical(v: &Vec3<f32>) -> (f32, f32, f32) {
+    let length = v.length();
+    if length == 0.0 {
+        return (0.0, 0.0, 0.0);
+    }
+
+    (
+        length,
+        v.x.atan2(v.z),
+        (v.y / length).clamp(-1.0, 1.0).asin(),
+    )
+}
+
+fn from_spherical(yaw: f32, pitch: f32) -> Vec3<f32> {
+    let pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+    Vec3::new(
+        pitch.cos() * yaw.sin(),
+        pitch.sin(),
+        pitch.cos() * yaw.cos(),
+    )
+}
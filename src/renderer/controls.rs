@@ -0,0 +1,91 @@
+use minifb::{Key, MouseButton};
+
+use crate::graphics::TinyRendererWindow;
+use crate::renderer::Camera;
+
+// radians of camera rotation per pixel of mouse movement
+const MOUSE_SENSITIVITY: f32 = 0.005;
+// zoom factor per scroll wheel step
+const SCROLL_ZOOM: f32 = 0.9;
+// world units moved per frame while a fly key is held
+const FLY_SPEED: f32 = 0.05;
+
+/// How mouse and keyboard input moves the camera in `TinyRenderer::update_camera`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraControls {
+    /// Dragging with the left mouse button orbits around the target and scrolling zooms.
+    Orbit,
+    /// WASD moves, Q/E move down and up, and dragging with the left mouse button looks around.
+    Fly,
+}
+
+/// Turns window input into camera movement, remembering where the mouse was last frame.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraController {
+    pub controls: CameraControls,
+    last_mouse_pos: Option<(f32, f32)>,
+}
+
+impl CameraController {
+    pub fn new(controls: CameraControls) -> CameraController {
+        CameraController {
+            controls,
+            last_mouse_pos: None,
+        }
+    }
+
+    /// Applies this frame's mouse and keyboard input to `camera`. Tab switches between orbit and
+    /// fly controls.
+    pub fn update(&mut self, window: &TinyRendererWindow, camera: &mut Camera) {
+        if window.is_key_pressed(Key::Tab) {
+            self.controls = match self.controls {
+                CameraControls::Orbit => CameraControls::Fly,
+                CameraControls::Fly => CameraControls::Orbit,
+            };
+        }
+
+        // only track the mouse while dragging, so the camera doesn't jump when a drag starts
+        let mouse_pos = if window.is_mouse_down(MouseButton::Left) {
+            window.mouse_pos()
+        } else {
+            None
+        };
+
+        let (dx, dy) = match (self.last_mouse_pos, mouse_pos) {
+            (Some((x0, y0)), Some((x1, y1))) => (x1 - x0, y1 - y0),
+            _ => (0.0, 0.0),
+        };
+        self.last_mouse_pos = mouse_pos;
+
+        if let Some((_, scroll)) = window.scroll_wheel() {
+            camera.zoom(SCROLL_ZOOM.powf(scroll.signum()));
+        }
+
+        match self.controls {
+            CameraControls::Orbit => {
+                // dragging right moves the camera left around the target so the model turns right
+                camera.orbit(-dx * MOUSE_SENSITIVITY, dy * MOUSE_SENSITIVITY);
+            }
+            CameraControls::Fly => {
+                camera.look(-dx * MOUSE_SENSITIVITY, -dy * MOUSE_SENSITIVITY);
+
+                let axis = |positive: Key, negative: Key| {
+                    let mut value = 0.0;
+                    if window.is_key_down(positive) {
+                        value += FLY_SPEED;
+                    }
+                    if window.is_key_down(negative) {
+                        value -= FLY_SPEED;
+                    }
+                    value
+                };
+
+                camera.fly(
+                    axis(Key::W, Key::S),
+                    axis(Key::D, Key::A),
+                    axis(Key::E, Key::Q),
+                );
+            }
+        }
+    }
+}
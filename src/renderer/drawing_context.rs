@@ -1,24 +1,84 @@
 use crate::{
-    algorithms::Algorithms,
-    graphics::{PixelPos, RenderTarget, color},
+    algorithms::{Algorithms, LineStyle, clip_line, draw_thick_polyline},
+    geometry::Rect,
+    graphics::{DepthBuffer, RenderTarget, color},
+    shader::{ScreenVertex, Shader, Uniforms},
 };
 
 pub struct DrawingContext<T: RenderTarget> {
     pub render_output: T,
+    pub depth_buffer: DepthBuffer,
     pub algorithms: Algorithms<T>,
     pub color: color::RGBA,
+    /// Lines are clipped to this as well as to the render output when set.
+    pub scissor: Option<Rect>,
 }
 
 impl<T: RenderTarget> DrawingContext<T> {
+    /// Draws a line with the line algorithm after clipping it to the render output and scissor
+    /// rect, so far off screen endpoints don't cost a loop over every pixel in between.
     pub fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32) {
-        (self.algorithms.draw_line_alg)(x0, y0, x1, y1, &self.color, &mut self.render_output)
+        let bounds = self.bounds();
+
+        if let Some((x0, y0, x1, y1)) = clip_line(x0, y0, x1, y1, &bounds) {
+            (self.algorithms.draw_line_alg)(x0, y0, x1, y1, &self.color, &mut self.render_output)
+        }
+    }
+
+    /// Strokes a polyline through screen space points with `style`, joining its last point back
+    /// to the first when `closed`. One pixel wide styles go through the line algorithm.
+    pub fn draw_polyline(&mut self, points: &[(f32, f32)], closed: bool, style: &LineStyle) {
+        if style.width > 1.0 {
+            // a tile of a larger target only needs the part of the stroke it holds
+            let bounds = self.bounds().intersect(&self.render_output.bounds());
+            draw_thick_polyline(
+                points,
+                closed,
+                style,
+                &self.color,
+                &bounds,
+                &mut self.render_output,
+            );
+            return;
+        }
+
+        let segment_count = if closed && points.len() > 2 {
+            points.len()
+        } else {
+            points.len().saturating_sub(1)
+        };
+        for i in 0..segment_count {
+            let (x0, y0) = points[i];
+            let (x1, y1) = points[(i + 1) % points.len()];
+            self.draw_line(x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+        }
     }
+
     pub fn rasterize_triangle(
         &mut self,
-        v0: &PixelPos,
-        v1: &PixelPos,
-        v2: &PixelPos,
+        v0: &ScreenVertex,
+        v1: &ScreenVertex,
+        v2: &ScreenVertex,
+        shader: &dyn Shader,
+        uniforms: &Uniforms,
     ) {
-        (self.algorithms.rasterize_triangle_alg)(v0, v1, v2, &self.color, &mut self.render_output)
+        (self.algorithms.rasterize_triangle_alg)(
+            v0,
+            v1,
+            v2,
+            shader,
+            uniforms,
+            &mut self.depth_buffer,
+            &mut self.render_output,
+        )
+    }
+
+    /// The render output's pixels, cut down to the scissor rect when there is one.
+    fn bounds(&self) -> Rect {
+        let bounds = Rect::from_size(self.render_output.width(), self.render_output.height());
+        match &self.scissor {
+            Some(scissor) => bounds.intersect(scissor),
+            None => bounds,
+        }
     }
 }
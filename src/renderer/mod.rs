@@ -1,8 +1,15 @@
 pub mod builder;
+pub mod camera;
+pub mod controls;
 pub mod drawing_context;
+#[allow(clippy::module_inception)]
 pub mod renderer;
+pub mod transform;
 
 pub use builder::TinyRendererBuilder;
+pub use camera::{Camera, Projection};
+pub use controls::{CameraController, CameraControls};
 pub use drawing_context::DrawingContext;
-pub use renderer::DrawType;
 pub use renderer::TinyRenderer;
+pub use renderer::{CullMode, DrawStats, DrawType, FrontFace, GroupStyle};
+pub use transform::Transform;
@@ -1,12 +1,35 @@
+use std::collections::HashMap;
 use std::error::Error;
+use std::panic;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 use minifb;
 
-use crate::algorithms::Algorithms;
-use crate::geometry::Vec4;
-use crate::graphics::{PixelPos, RenderTarget, TinyRendererWindow, color};
-use crate::mesh::Mesh;
-use crate::renderer::DrawingContext;
+use crate::algorithms::{Algorithms, LineStyle};
+use crate::geometry::{Mat3, Mat4, Rect, Vec3, Vec4};
+use crate::graphics::{DepthBuffer, Framebuffer, RenderTarget, Texture, TinyRendererWindow, color};
+use crate::lighting::{Light, Material, ShadingModel};
+use crate::mesh::{FaceElement, Mesh};
+use crate::renderer::{Camera, CameraController, CameraControls, DrawingContext, Transform};
+use crate::shader::{
+    ColorShader, FlatShader, GouraudShader, PhongShader, ScreenVertex, Shader, Uniforms, Varyings,
+    VertexInput,
+};
+
+// how far past the viewport, in multiples of its half size, triangles are left for the rasterizer
+// to clamp instead of being clipped
+const GUARD_BAND: f32 = 2.0;
+// a triangle clipped against 6 planes gains at most one vertex per plane
+const MAX_CLIPPED_VERTICES: usize = 9;
+// enough that faces turned away from every light still show their shape
+const DEFAULT_AMBIENT_LIGHT: color::RGBA = color::RGBA {
+    r: 32,
+    g: 32,
+    b: 32,
+    a: 255,
+};
 
 #[derive(Debug)]
 pub enum DrawType {
@@ -14,49 +37,152 @@ pub enum DrawType {
     Line,
 }
 
+/// Which faces of a mesh are skipped, decided by their winding on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CullMode {
+    None,
+    Back,
+    Front,
+}
+
+/// The screen space winding order of front facing triangles. With y pointing up, counter
+/// clockwise matches the OBJ convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontFace {
+    CounterClockwise,
+    Clockwise,
+}
+
+/// How the faces of a named group of a mesh are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupStyle {
+    pub visible: bool,
+    /// Fills the group with this color instead of its material or the drawing color.
+    pub color: Option<color::RGBA>,
+}
+
+impl Default for GroupStyle {
+    fn default() -> GroupStyle {
+        GroupStyle {
+            visible: true,
+            color: None,
+        }
+    }
+}
+
+/// Counts from the most recent call to `TinyRenderer::draw`. Each mesh face counts once, even
+/// when it is drawn as several triangles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    /// Faces with at least one triangle drawn.
+    pub drawn_faces: usize,
+    /// Faces that weren't drawn because their triangles faced the culled way.
+    pub culled_faces: usize,
+    /// Faces entirely outside the view volume.
+    pub clipped_faces: usize,
+}
+
 pub struct TinyRenderer<T: RenderTarget> {
-    meshes: Vec<Mesh>,
+    meshes: Vec<Rc<Mesh>>,
     draw_types: Vec<DrawType>,
+    cull_modes: Vec<CullMode>,
+    line_styles: Vec<LineStyle>,
+    transforms: Vec<Transform>,
+    shaders: Vec<MeshShader>,
+    textures: Vec<Option<Rc<Texture>>>,
+    materials: Vec<Material>,
+    group_styles: Vec<HashMap<String, GroupStyle>>,
+    lights: Vec<Light>,
+    ambient_light: color::RGBA,
+    camera: Camera,
+    camera_controller: CameraController,
+    front_face: FrontFace,
+    stats: DrawStats,
     drawing_ctx: DrawingContext<T>,
 }
 
 impl<T: RenderTarget> TinyRenderer<T> {
     pub fn new(render_output: T, algorithms: Algorithms<T>, color: color::RGBA) -> TinyRenderer<T> {
+        let depth_buffer = DepthBuffer::new(render_output.width(), render_output.height());
+
         TinyRenderer {
             draw_types: Vec::new(),
+            cull_modes: Vec::new(),
+            line_styles: Vec::new(),
             meshes: Vec::new(),
+            transforms: Vec::new(),
+            shaders: Vec::new(),
+            textures: Vec::new(),
+            materials: Vec::new(),
+            group_styles: Vec::new(),
+            lights: Vec::new(),
+            ambient_light: DEFAULT_AMBIENT_LIGHT,
+            camera: Camera::default(),
+            camera_controller: CameraController::new(CameraControls::Orbit),
+            front_face: FrontFace::CounterClockwise,
+            stats: DrawStats::default(),
             drawing_ctx: DrawingContext {
                 render_output,
+                depth_buffer,
                 algorithms,
                 color,
+                scissor: None,
             },
         }
     }
 
     pub fn add_mesh(&mut self, mesh: Mesh) -> usize {
-        self.meshes.push(mesh);
-        self.draw_types.push(DrawType::Fill);
-        self.meshes.len() - 1
+        self.push_mesh(Rc::new(mesh))
     }
 
-    pub fn scale_vertices(&mut self, id: usize, scale: f32) {
+    /// Adds another instance of an already added mesh. The instance shares the vertex data but
+    /// gets its own draw type and transform.
+    pub fn add_mesh_instance(&mut self, id: usize) -> usize {
         self.check_mesh_range(&id);
-        if id > self.meshes.len() - 1 {
-            panic!("referencing an invalid mesh");
-        }
-        for vertice in self.meshes[id].vertices.iter_mut() {
-            vertice.x = scale * vertice.x;
-            vertice.y = scale * vertice.y;
-            vertice.z = scale * vertice.z;
-        }
+        self.push_mesh(Rc::clone(&self.meshes[id]))
     }
 
-    pub fn move_vertices(&mut self, id: usize, x: f32, y: f32) {
+    pub fn mesh(&self, id: usize) -> &Mesh {
         self.check_mesh_range(&id);
-        for vertice in self.meshes[id].vertices.iter_mut() {
-            vertice.x = x + vertice.x;
-            vertice.y = y + vertice.y;
-        }
+        &self.meshes[id]
+    }
+
+    pub fn transform(&self, id: usize) -> &Transform {
+        self.check_mesh_range(&id);
+        &self.transforms[id]
+    }
+
+    pub fn set_transform(&mut self, id: usize, transform: Transform) {
+        self.check_mesh_range(&id);
+        self.transforms[id] = transform;
+    }
+
+    pub fn translation(&self, id: usize) -> Vec3<f32> {
+        self.transform(id).translation
+    }
+
+    pub fn set_translation(&mut self, id: usize, translation: Vec3<f32>) {
+        self.check_mesh_range(&id);
+        self.transforms[id].translation = translation;
+    }
+
+    pub fn rotation(&self, id: usize) -> Vec3<f32> {
+        self.transform(id).rotation
+    }
+
+    /// Sets the rotation as Euler angles in radians, applied around x, then y, then z.
+    pub fn set_rotation(&mut self, id: usize, rotation: Vec3<f32>) {
+        self.check_mesh_range(&id);
+        self.transforms[id].rotation = rotation;
+    }
+
+    pub fn scale(&self, id: usize) -> Vec3<f32> {
+        self.transform(id).scale
+    }
+
+    pub fn set_scale(&mut self, id: usize, scale: Vec3<f32>) {
+        self.check_mesh_range(&id);
+        self.transforms[id].scale = scale;
     }
 
     pub fn set_draw_type(&mut self, id: usize, draw_type: DrawType) {
@@ -64,62 +190,285 @@ impl<T: RenderTarget> TinyRenderer<T> {
         self.draw_types[id] = draw_type;
     }
 
+    /// Sets which faces of the mesh are skipped. Meshes start out with `CullMode::None`, so
+    /// open meshes and meshes with inconsistent winding still draw every face.
+    pub fn set_cull_mode(&mut self, id: usize, cull_mode: CullMode) {
+        self.check_mesh_range(&id);
+        self.cull_modes[id] = cull_mode;
+    }
+
+    pub fn cull_mode(&self, id: usize) -> CullMode {
+        self.check_mesh_range(&id);
+        self.cull_modes[id]
+    }
+
+    /// Sets the width, caps and joins of the mesh's wireframe edges when drawn with
+    /// `DrawType::Line`. Meshes start out 1 pixel wide.
+    pub fn set_line_style(&mut self, id: usize, line_style: LineStyle) {
+        self.check_mesh_range(&id);
+        self.line_styles[id] = line_style;
+    }
+
+    pub fn line_style(&self, id: usize) -> LineStyle {
+        self.check_mesh_range(&id);
+        self.line_styles[id]
+    }
+
+    /// Replaces the shader used to fill the mesh. Meshes start out with a `ColorShader`. Shaders
+    /// set this way can't be shared between threads, so `draw_tiled` refuses to draw the mesh; use
+    /// `set_sync_shader` for shaders that are `Sync`.
+    pub fn set_shader<S: Shader + 'static>(&mut self, id: usize, shader: S) {
+        self.check_mesh_range(&id);
+        self.shaders[id] = MeshShader::Local(Box::new(shader));
+    }
+
+    /// Replaces the shader used to fill the mesh with one that `draw_tiled` can share between its
+    /// threads.
+    pub fn set_sync_shader<S: Shader + Sync + 'static>(&mut self, id: usize, shader: S) {
+        self.check_mesh_range(&id);
+        self.shaders[id] = MeshShader::Shared(Box::new(shader));
+    }
+
+    /// Binds a diffuse texture to the mesh, which shaders read from `Uniforms::diffuse_texture`.
+    /// The texture is sampled with the mesh's OBJ `vt` coordinates by `TextureShader` and the lit
+    /// shaders of `set_shading_model`, but not by the default `ColorShader`, so a textured mesh
+    /// needs one of those set. Faces with an MTL material use the material's color and texture
+    /// instead.
+    pub fn set_texture(&mut self, id: usize, texture: Rc<Texture>) {
+        self.check_mesh_range(&id);
+        self.textures[id] = Some(texture);
+    }
+
+    pub fn texture(&self, id: usize) -> Option<&Rc<Texture>> {
+        self.check_mesh_range(&id);
+        self.textures[id].as_ref()
+    }
+
+    /// Fills the mesh with one of the built in lit shaders, replacing its current shader.
+    pub fn set_shading_model(&mut self, id: usize, shading_model: ShadingModel) {
+        match shading_model {
+            ShadingModel::Flat => self.set_sync_shader(id, FlatShader),
+            ShadingModel::Gouraud => self.set_sync_shader(id, GouraudShader),
+            ShadingModel::Phong => self.set_sync_shader(id, PhongShader),
+        }
+    }
+
+    /// Sets the specular color and shininess lit shaders use for the mesh's faces without an MTL
+    /// material.
+    pub fn set_material(&mut self, id: usize, material: Material) {
+        self.check_mesh_range(&id);
+        self.materials[id] = material;
+    }
+
+    pub fn material(&self, id: usize) -> &Material {
+        self.check_mesh_range(&id);
+        &self.materials[id]
+    }
+
+    /// How the object or group `name` of the mesh is drawn. Panics if the mesh has no such group.
+    pub fn group_style(&self, id: usize, name: &str) -> GroupStyle {
+        self.check_group(id, name);
+        self.group_styles[id].get(name).copied().unwrap_or_default()
+    }
+
+    /// Shows or hides the faces of the object or group `name` of the mesh. A face in several
+    /// groups is hidden when any of them is. Panics if the mesh has no such group.
+    pub fn set_group_visible(&mut self, id: usize, name: &str, visible: bool) {
+        self.check_group(id, name);
+        self.group_styles[id]
+            .entry(name.to_string())
+            .or_default()
+            .visible = visible;
+    }
+
+    /// Fills the faces of the object or group `name` of the mesh with `color`, or with their
+    /// material again for `None`. Where recolored groups overlap, the one that ends last in the
+    /// OBJ file wins. Panics if the mesh has no such group.
+    pub fn set_group_color(&mut self, id: usize, name: &str, color: Option<color::RGBA>) {
+        self.check_group(id, name);
+        self.group_styles[id]
+            .entry(name.to_string())
+            .or_default()
+            .color = color;
+    }
+
+    /// Adds a light for the lit shaders, getting back its id.
+    pub fn add_light(&mut self, light: Light) -> usize {
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Panics if no light was added with `id`.
+    pub fn light_mut(&mut self, id: usize) -> &mut Light {
+        self.lights
+            .get_mut(id)
+            .expect("Error In Renderer: Referencing an invalid light.")
+    }
+
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+    }
+
+    pub fn ambient_light(&self) -> color::RGBA {
+        self.ambient_light
+    }
+
+    /// Sets the light that reaches every surface regardless of the lights. Starts out a dim gray.
+    pub fn set_ambient_light(&mut self, ambient_light: color::RGBA) {
+        self.ambient_light = ambient_light;
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    /// How `update_camera` moves the camera on window renderers.
+    pub fn camera_controls(&self) -> CameraControls {
+        self.camera_controller.controls
+    }
+
+    pub fn set_camera_controls(&mut self, camera_controls: CameraControls) {
+        self.camera_controller.controls = camera_controls;
+    }
+
+    pub fn scissor(&self) -> Option<Rect> {
+        self.drawing_ctx.scissor
+    }
+
+    /// Restricts line drawing to a rectangle of the render output, or lifts the restriction.
+    pub fn set_scissor(&mut self, scissor: Option<Rect>) {
+        self.drawing_ctx.scissor = scissor;
+    }
+
+    pub fn front_face(&self) -> FrontFace {
+        self.front_face
+    }
+
+    pub fn set_front_face(&mut self, front_face: FrontFace) {
+        self.front_face = front_face;
+    }
+
+    /// How many faces the last `draw` rasterized and how many it culled.
+    pub fn stats(&self) -> DrawStats {
+        self.stats
+    }
+
     pub fn set_render_output(&mut self, render_output: T) {
+        self.drawing_ctx.depth_buffer =
+            DepthBuffer::new(render_output.width(), render_output.height());
         self.drawing_ctx.render_output = render_output;
     }
 
+    pub fn render_output(&self) -> &T {
+        &self.drawing_ctx.render_output
+    }
+
+    pub fn depth_buffer(&self) -> &DepthBuffer {
+        &self.drawing_ctx.depth_buffer
+    }
+
+    /// Resets every depth to the far plane, so the next `draw` isn't hidden behind the last one.
+    pub fn clear_depth(&mut self) {
+        self.drawing_ctx.depth_buffer.clear();
+    }
+
     pub fn draw(&mut self) -> Result<(), Box<dyn Error>> {
-        for (i, mesh) in self.meshes.iter_mut().enumerate() {
-            let mut screen_space_coordinates: Vec<PixelPos> = Vec::new();
-
-            for vertice in mesh.vertices.iter() {
-                screen_space_coordinates.push(
-                    world_to_screen_space(
-                        self.drawing_ctx.render_output.width(),
-                        self.drawing_ctx.render_output.height(),
-                        vertice,
-                    )
-                )
-            }
+        let frame = self.frame();
+        let uniforms = mesh_uniforms(
+            &frame,
+            &self.meshes,
+            &self.transforms,
+            &self.textures,
+            &self.materials,
+            &self.group_styles,
+            &self.lights,
+        );
+        let shaders: Vec<_> = self.shaders.iter().map(MeshShader::get).collect();
+        let context = PrimitiveContext {
+            shaders: &shaders,
+            uniforms: &uniforms,
+            line_styles: &self.line_styles,
+        };
 
-            for face in &mesh.faces {
-                let get_screen_space_coordinate = |idx: Option<i32>| -> Result<&PixelPos, Box<dyn Error>> {
-                    idx.ok_or("Face missing vertex index")?
-                        .try_into()
-                        .ok()
-                        .and_then(|i: usize| screen_space_coordinates.get(i))
-                        .ok_or_else(|| "Invalid vertex index".into())
-                };
-
-                let v0 = get_screen_space_coordinate(face[0].vertex_index)?;
-                let v1 = get_screen_space_coordinate(face[1].vertex_index)?;
-                let v2 = get_screen_space_coordinate(face[2].vertex_index)?;
-
-                match &self.draw_types[i] {
-                    DrawType::Fill => self.drawing_ctx.rasterize_triangle(v0, v1, v2),
-                    DrawType::Line => {
-                        self.drawing_ctx.draw_line(v0.x, v0.y, v1.x, v1.y);
-                        self.drawing_ctx.draw_line(v1.x, v1.y, v2.x, v2.y);
-                        self.drawing_ctx.draw_line(v2.x, v2.y, v0.x, v0.y);
-                    }
-                }
-            }
+        // primitives are rasterized as they come, so nothing is buffered
+        let drawing_ctx = &mut self.drawing_ctx;
+        self.stats = Scene {
+            meshes: &self.meshes,
+            shaders: &shaders,
+            draw_types: &self.draw_types,
+            cull_modes: &self.cull_modes,
+            group_styles: &self.group_styles,
+            front_face: self.front_face,
         }
+        .primitives(&frame, &uniforms, |primitive| {
+            context.draw(drawing_ctx, &primitive)
+        })?;
 
         self.drawing_ctx.render_output.render()?;
         Ok(())
     }
 
+    /// The matrices every mesh shares for one draw.
+    fn frame(&self) -> Frame {
+        let width = self.drawing_ctx.render_output.width() as f32;
+        let height = self.drawing_ctx.render_output.height() as f32;
+
+        let view = self.camera.view_matrix();
+        let projection = self.camera.projection_matrix(width / height);
+
+        Frame {
+            viewport: Mat4::viewport(0.0, 0.0, width, height),
+            view_projection: &projection * &view,
+            view,
+            projection,
+            camera_position: self.camera.position,
+            color: self.drawing_ctx.color,
+            ambient_light: self.ambient_light,
+        }
+    }
+
+    fn push_mesh(&mut self, mesh: Rc<Mesh>) -> usize {
+        self.meshes.push(mesh);
+        self.draw_types.push(DrawType::Fill);
+        self.cull_modes.push(CullMode::None);
+        self.line_styles.push(LineStyle::default());
+        self.transforms.push(Transform::new());
+        self.shaders.push(MeshShader::Shared(Box::new(ColorShader)));
+        self.textures.push(None);
+        self.materials.push(Material::default());
+        self.group_styles.push(HashMap::new());
+        self.meshes.len() - 1
+    }
+
     fn check_mesh_range(&self, id: &usize) {
         if *id > self.meshes.len() - 1 {
             panic!("Error In Renderer: Referencing an invalid mesh.")
         }
     }
+
+    fn check_group(&self, id: usize, name: &str) {
+        if self.mesh(id).group_faces(name).next().is_none() {
+            panic!("Error In Renderer: Referencing an invalid group.")
+        }
+    }
 }
 
 impl TinyRenderer<TinyRendererWindow> {
     pub fn clear(&mut self) {
         self.drawing_ctx.render_output.clear();
+        self.clear_depth();
     }
 
     pub fn is_open(&self) -> bool {
@@ -129,15 +478,814 @@ impl TinyRenderer<TinyRendererWindow> {
     pub fn is_key_down(&self, key: minifb::Key) -> bool {
         self.drawing_ctx.render_output.is_key_down(key)
     }
+
+    /// Moves the camera according to the window's mouse and keyboard state. Call once per frame.
+    pub fn update_camera(&mut self) {
+        self.camera_controller
+            .update(&self.drawing_ctx.render_output, &mut self.camera);
+    }
 }
 
-fn world_to_screen_space(
-    width: usize,
-    height: usize,
-    world_coordinates: &Vec4<f32>,
-) -> PixelPos {
-    PixelPos {
-        x: ((world_coordinates.x + 1.0) * width as f32 / 2.0) as i32,
-        y: ((world_coordinates.y + 1.0) * height as f32 / 2.0) as i32,
+impl TinyRenderer<Framebuffer> {
+    /// Draws the same image as `draw`, but splits the framebuffer into `tile_size` pixel square
+    /// tiles that are rasterized in parallel on `thread_count` threads.
+    ///
+    /// Faces are transformed, clipped and culled up front, then binned into every tile their
+    /// screen bounds overlap. Each tile draws its bin in submission order into its own copy of
+    /// that part of the framebuffer and depth buffer, so every pixel sees the same writes in the
+    /// same order as on a single thread and the result is byte-identical.
+    ///
+    /// The threads share the meshes' shaders, so every mesh needs one set with `set_sync_shader`
+    /// or `set_shading_model`, or to keep its default `ColorShader`. Otherwise an error is
+    /// returned and nothing is drawn.
+    pub fn draw_tiled(
+        &mut self,
+        tile_size: usize,
+        thread_count: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        if tile_size == 0 || thread_count == 0 {
+            return Err("Tile size and thread count must be at least 1".into());
+        }
+
+        let frame = self.frame();
+        let uniforms = mesh_uniforms(
+            &frame,
+            &self.meshes,
+            &self.transforms,
+            &self.textures,
+            &self.materials,
+            &self.group_styles,
+            &self.lights,
+        );
+        let sync_shaders = self
+            .shaders
+            .iter()
+            .enumerate()
+            .map(|(i, shader)| {
+                shader.shared().ok_or_else(|| {
+                    format!(
+                        "The shader of mesh {} isn't Sync, set it with set_sync_shader to draw tiled",
+                        i
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let shaders: Vec<&dyn Shader> = sync_shaders.iter().map(|&shader| shader as _).collect();
+
+        let mut primitives = Vec::new();
+        self.stats = Scene {
+            meshes: &self.meshes,
+            shaders: &shaders,
+            draw_types: &self.draw_types,
+            cull_modes: &self.cull_modes,
+            group_styles: &self.group_styles,
+            front_face: self.front_face,
+        }
+        .primitives(&frame, &uniforms, |primitive| primitives.push(primitive))?;
+
+        let context = PrimitiveContext {
+            shaders: &shaders,
+            uniforms: &uniforms,
+            line_styles: &self.line_styles,
+        };
+        let tiles = bin_primitives(
+            &primitives,
+            &context,
+            &self.drawing_ctx.render_output.bounds(),
+            tile_size,
+        );
+
+        // tiles are handed out one at a time, so threads that get cheap tiles take more of them
+        let next_tile = AtomicUsize::new(0);
+        let drawing_ctx = &self.drawing_ctx;
+        let (uniforms, line_styles) = (&uniforms, &self.line_styles);
+        let finished: Vec<(Framebuffer, DepthBuffer)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..thread_count.min(tiles.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        // only the Sync shaders cross threads, each worker lends them out itself
+                        let shaders: Vec<&dyn Shader> =
+                            sync_shaders.iter().map(|&shader| shader as _).collect();
+                        let context = PrimitiveContext {
+                            shaders: &shaders,
+                            uniforms,
+                            line_styles,
+                        };
+                        let mut finished = Vec::new();
+
+                        loop {
+                            let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                            let Some((region, bin)) = tiles.get(index) else {
+                                break;
+                            };
+
+                            let mut tile_ctx = DrawingContext {
+                                render_output: drawing_ctx.render_output.tile(region),
+                                depth_buffer: drawing_ctx.depth_buffer.tile(region),
+                                algorithms: drawing_ctx.algorithms,
+                                color: drawing_ctx.color,
+                                scissor: drawing_ctx.scissor,
+                            };
+                            for &primitive in bin {
+                                context.draw(&mut tile_ctx, &primitives[primitive]);
+                            }
+
+                            finished.push((tile_ctx.render_output, tile_ctx.depth_buffer));
+                        }
+
+                        finished
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                // a panicking shader panics the draw, just as it would on one thread
+                .flat_map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|panic| panic::resume_unwind(panic))
+                })
+                .collect()
+        });
+
+        // tiles don't overlap, so the order they are written back in doesn't matter
+        for (color, depth) in finished {
+            color.copy_to(&mut self.drawing_ctx.render_output);
+            self.drawing_ctx.depth_buffer.copy_from_tile(&depth);
+        }
+
+        self.drawing_ctx.render_output.render()?;
+        Ok(())
     }
 }
+
+/// What every mesh shares for one draw.
+struct Frame {
+    viewport: Mat4,
+    view: Mat4,
+    projection: Mat4,
+    view_projection: Mat4,
+    camera_position: Vec3<f32>,
+    color: color::RGBA,
+    ambient_light: color::RGBA,
+}
+
+/// The uniforms of each mesh, borrowing only the fields they need so the drawing context can
+/// still be written while they are alive. The first uniforms of a mesh are for faces without a
+/// material, and use the drawing color, the mesh's texture and its material. Next come the mesh's
+/// MTL materials, each using its own diffuse color or texture and specular, and then one for each
+/// recolored group range in the order of `Mesh::groups`.
+fn mesh_uniforms<'a>(
+    frame: &Frame,
+    meshes: &'a [Rc<Mesh>],
+    transforms: &[Transform],
+    textures: &'a [Option<Rc<Texture>>],
+    materials: &[Material],
+    group_styles: &[HashMap<String, GroupStyle>],
+    lights: &'a [Light],
+) -> Vec<Vec<Uniforms<'a>>> {
+    meshes
+        .iter()
+        .zip(transforms)
+        .zip(textures)
+        .zip(materials)
+        .zip(group_styles)
+        .map(|((((mesh, transform), texture), material), group_styles)| {
+            let model = transform.to_matrix();
+            let uniforms = Uniforms {
+                model,
+                view: frame.view,
+                projection: frame.projection,
+                model_view_projection: &frame.view_projection * &model,
+                normal_matrix: Mat3::from_mat4(&model)
+                    .inverse()
+                    .unwrap_or_else(Mat3::identity)
+                    .transpose(),
+                camera_position: frame.camera_position,
+                color: frame.color,
+                diffuse_texture: texture.as_deref(),
+                lights,
+                ambient_light: frame.ambient_light,
+                material: *material,
+            };
+
+            let material_uniforms = mesh.materials.iter().map(|material| Uniforms {
+                color: material.diffuse_color(),
+                diffuse_texture: material.diffuse_texture.as_deref(),
+                material: material.lighting_material(),
+                ..uniforms
+            });
+            let group_uniforms = group_colors(mesh, group_styles).map(|color| Uniforms {
+                color,
+                diffuse_texture: None,
+                ..uniforms
+            });
+
+            std::iter::once(uniforms)
+                .chain(material_uniforms)
+                .chain(group_uniforms)
+                .collect()
+        })
+        .collect()
+}
+
+/// The colors of the mesh's recolored group ranges, in the order of `Mesh::groups`.
+fn group_colors<'a>(
+    mesh: &'a Mesh,
+    group_styles: &'a HashMap<String, GroupStyle>,
+) -> impl Iterator<Item = color::RGBA> + 'a {
+    mesh.groups
+        .iter()
+        .filter_map(|group| group_styles.get(&group.name)?.color)
+}
+
+/// Which of the mesh's uniforms, laid out by `mesh_uniforms`, each face is drawn with, or `None`
+/// for faces in a hidden group.
+fn face_uniform_slots(
+    mesh: &Mesh,
+    group_styles: &HashMap<String, GroupStyle>,
+) -> Vec<Option<usize>> {
+    let mut slots: Vec<Option<usize>> = mesh
+        .face_materials()
+        .iter()
+        .map(|material| Some(material.map_or(0, |material| material + 1)))
+        .collect();
+
+    let mut next_group_slot = mesh.materials.len() + 1;
+    for group in &mesh.groups {
+        let style = group_styles.get(&group.name).copied().unwrap_or_default();
+        let faces = group.faces.start.min(slots.len())..group.faces.end.min(slots.len());
+
+        if style.color.is_some() {
+            slots[faces.clone()].fill(Some(next_group_slot));
+            next_group_slot += 1;
+        }
+    }
+
+    // hidden groups win over recolored ones, whichever comes first
+    for group in &mesh.groups {
+        let style = group_styles.get(&group.name).copied().unwrap_or_default();
+        let faces = group.faces.start.min(slots.len())..group.faces.end.min(slots.len());
+
+        if !style.visible {
+            slots[faces].fill(None);
+        }
+    }
+
+    slots
+}
+
+/// The meshes and the per-mesh state that decide what a draw rasterizes, borrowed apart from the
+/// drawing context so that primitives can be drawn while they are produced.
+struct Scene<'a> {
+    meshes: &'a [Rc<Mesh>],
+    shaders: &'a [&'a dyn Shader],
+    draw_types: &'a [DrawType],
+    cull_modes: &'a [CullMode],
+    group_styles: &'a [HashMap<String, GroupStyle>],
+    front_face: FrontFace,
+}
+
+impl Scene<'_> {
+    /// Runs every face through the vertex shader, clipping and culling, and hands what is left
+    /// to rasterize to `emit` in the order it must be drawn.
+    fn primitives(
+        &self,
+        frame: &Frame,
+        uniforms: &[Vec<Uniforms>],
+        mut emit: impl FnMut(Primitive),
+    ) -> Result<DrawStats, Box<dyn Error>> {
+        let mut stats = DrawStats::default();
+
+        for (i, mesh) in self.meshes.iter().enumerate() {
+            let shader = self.shaders[i];
+            let face_slots = face_uniform_slots(mesh, &self.group_styles[i]);
+
+            let faces = mesh
+                .faces
+                .iter()
+                .zip(face_slots)
+                // faces in hidden groups have no slot
+                .filter_map(|(face, slot)| Some((face, slot?)));
+            for (face, slot) in faces {
+                // a face is counted once however many triangles it is fanned into: as drawn if
+                // any of them is, otherwise as culled if any of them faced away
+                let (mut drawn, mut culled, mut clipped) = (false, false, false);
+                let center = face_center(mesh, face);
+
+                for corners in fan(face) {
+                    let mut triangle = [ClipVertex::default(); 3];
+
+                    for (input, clip_vertex) in face_inputs(mesh, corners, center)?
+                        .iter()
+                        .zip(&mut triangle)
+                    {
+                        let mut varyings = Varyings::new();
+                        let position = shader.vertex(input, &uniforms[i][slot], &mut varyings);
+
+                        *clip_vertex = ClipVertex { position, varyings };
+                    }
+
+                    // clip before the perspective divide, so nothing behind the camera gets flipped
+                    let polygon = clip_triangle(&triangle);
+                    if polygon.len < 3 {
+                        clipped = true;
+                        continue;
+                    }
+
+                    let mut screen_vertices = [ScreenVertex {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                        inv_w: 1.0,
+                        varyings: Varyings::new(),
+                    }; MAX_CLIPPED_VERTICES];
+                    for (screen_vertex, clip_vertex) in
+                        screen_vertices.iter_mut().zip(polygon.vertices())
+                    {
+                        *screen_vertex = clip_to_screen_space(
+                            &frame.viewport,
+                            &clip_vertex.position,
+                            clip_vertex.varyings,
+                        );
+                    }
+                    let screen_vertices = &screen_vertices[..polygon.len];
+
+                    if is_culled(self.cull_modes[i], self.front_face, screen_vertices) {
+                        culled = true;
+                        continue;
+                    }
+                    drawn = true;
+
+                    match &self.draw_types[i] {
+                        DrawType::Fill => {
+                            // clipping a triangle leaves a convex polygon, so a fan covers it
+                            let v0 = screen_vertices[0];
+                            for pair in screen_vertices[1..].windows(2) {
+                                emit(Primitive::Triangle {
+                                    mesh: i,
+                                    material: slot,
+                                    vertices: [v0, pair[0], pair[1]],
+                                });
+                            }
+                        }
+                        DrawType::Line => {
+                            // faces that needed no clipping are stroked as one closed outline, so
+                            // thick edges get joins at the corners
+                            if triangle.iter().all(is_inside_view_volume) {
+                                emit(Primitive::Polyline {
+                                    mesh: i,
+                                    points: [0, 1, 2].map(|corner| {
+                                        (screen_vertices[corner].x, screen_vertices[corner].y)
+                                    }),
+                                    len: 3,
+                                    closed: true,
+                                });
+                                continue;
+                            }
+
+                            // otherwise clip the face's own edges rather than outlining the clipped
+                            // polygon, which would draw edges along the clip planes
+                            for corner in 0..3 {
+                                let a = &triangle[corner].position;
+                                let b = &triangle[(corner + 1) % 3].position;
+
+                                if let Some((a, b)) = clip_line(a, b) {
+                                    let a =
+                                        clip_to_screen_space(&frame.viewport, &a, Varyings::new());
+                                    let b =
+                                        clip_to_screen_space(&frame.viewport, &b, Varyings::new());
+
+                                    emit(Primitive::Polyline {
+                                        mesh: i,
+                                        points: [(a.x, a.y), (b.x, b.y), (0.0, 0.0)],
+                                        len: 2,
+                                        closed: false,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if drawn {
+                    stats.drawn_faces += 1;
+                } else if culled {
+                    stats.culled_faces += 1;
+                } else if clipped {
+                    stats.clipped_faces += 1;
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+}
+
+/// A mesh's shader. Only shaders known to be `Sync` can be shared with `draw_tiled`'s threads.
+enum MeshShader {
+    Local(Box<dyn Shader>),
+    Shared(Box<dyn Shader + Sync>),
+}
+
+impl MeshShader {
+    fn get(&self) -> &dyn Shader {
+        match self {
+            MeshShader::Local(shader) => shader.as_ref(),
+            MeshShader::Shared(shader) => shader.as_ref(),
+        }
+    }
+
+    fn shared(&self) -> Option<&(dyn Shader + Sync)> {
+        match self {
+            MeshShader::Local(_) => None,
+            MeshShader::Shared(shader) => Some(shader.as_ref()),
+        }
+    }
+}
+
+/// A clipped, culled piece of a face in screen space, ready to rasterize.
+// nearly every primitive is a triangle, so boxing them would only add an allocation per face
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Copy)]
+enum Primitive {
+    Triangle {
+        mesh: usize,
+        /// Which of the mesh's uniforms it is drawn with.
+        material: usize,
+        vertices: [ScreenVertex; 3],
+    },
+    Polyline {
+        mesh: usize,
+        points: [(f32, f32); 3],
+        len: usize,
+        closed: bool,
+    },
+}
+
+/// The per-mesh state primitives are drawn with.
+struct PrimitiveContext<'a> {
+    shaders: &'a [&'a dyn Shader],
+    uniforms: &'a [Vec<Uniforms<'a>>],
+    line_styles: &'a [LineStyle],
+}
+
+impl PrimitiveContext<'_> {
+    fn draw<T: RenderTarget>(&self, drawing_ctx: &mut DrawingContext<T>, primitive: &Primitive) {
+        match primitive {
+            Primitive::Triangle {
+                mesh,
+                material,
+                vertices,
+            } => {
+                let [v0, v1, v2] = vertices;
+                drawing_ctx.rasterize_triangle(
+                    v0,
+                    v1,
+                    v2,
+                    self.shaders[*mesh],
+                    &self.uniforms[*mesh][*material],
+                );
+            }
+            Primitive::Polyline {
+                mesh,
+                points,
+                len,
+                closed,
+            } => {
+                drawing_ctx.draw_polyline(&points[..*len], *closed, &self.line_styles[*mesh]);
+            }
+        }
+    }
+
+    /// The pixels a primitive can touch, padded so that rounding in the rasterizers and the
+    /// neighbours anti-aliased lines blend into are always inside.
+    fn bounds(&self, primitive: &Primitive) -> Rect {
+        let (points, padding) = match primitive {
+            Primitive::Triangle { vertices, .. } => (vertices.map(|v| (v.x, v.y)).to_vec(), 0.0),
+            Primitive::Polyline {
+                mesh, points, len, ..
+            } => (points[..*len].to_vec(), self.line_styles[*mesh].extent()),
+        };
+
+        let (mut min_x, mut min_y) = (f32::INFINITY, f32::INFINITY);
+        let (mut max_x, mut max_y) = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for (x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        Rect::new(
+            (min_x - padding).floor() as i32 - 2,
+            (min_y - padding).floor() as i32 - 2,
+            (max_x + padding).ceil() as i32 + 2,
+            (max_y + padding).ceil() as i32 + 2,
+        )
+    }
+}
+
+/// Splits `bounds` into square tiles and lists, for each tile that has any, the primitives
+/// overlapping it in submission order.
+fn bin_primitives(
+    primitives: &[Primitive],
+    context: &PrimitiveContext,
+    bounds: &Rect,
+    tile_size: usize,
+) -> Vec<(Rect, Vec<usize>)> {
+    if bounds.is_empty() {
+        return Vec::new();
+    }
+
+    let tile_size = tile_size.min(i32::MAX as usize) as i32;
+    let columns = (bounds.x_max - bounds.x_min) / tile_size + 1;
+    let rows = (bounds.y_max - bounds.y_min) / tile_size + 1;
+    let mut bins = vec![Vec::new(); (columns * rows) as usize];
+
+    for (index, primitive) in primitives.iter().enumerate() {
+        let overlap = context.bounds(primitive).intersect(bounds);
+        if overlap.is_empty() {
+            continue;
+        }
+
+        for row in
+            (overlap.y_min - bounds.y_min) / tile_size..=(overlap.y_max - bounds.y_min) / tile_size
+        {
+            for column in (overlap.x_min - bounds.x_min) / tile_size
+                ..=(overlap.x_max - bounds.x_min) / tile_size
+            {
+                bins[(row * columns + column) as usize].push(index);
+            }
+        }
+    }
+
+    bins.into_iter()
+        .enumerate()
+        .filter(|(_, bin)| !bin.is_empty())
+        .map(|(i, bin)| {
+            let (column, row) = (i as i32 % columns, i as i32 / columns);
+            let x_min = bounds.x_min + column * tile_size;
+            let y_min = bounds.y_min + row * tile_size;
+            let region = Rect::new(
+                x_min,
+                y_min,
+                x_min.saturating_add(tile_size - 1),
+                y_min.saturating_add(tile_size - 1),
+            )
+            .intersect(bounds);
+
+            (region, bin)
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClipVertex {
+    position: Vec4<f32>,
+    varyings: Varyings,
+}
+
+impl Default for ClipVertex {
+    fn default() -> ClipVertex {
+        ClipVertex {
+            position: Vec4::new(0.0, 0.0, 0.0, 1.0),
+            varyings: Varyings::new(),
+        }
+    }
+}
+
+impl ClipVertex {
+    fn lerp(a: &ClipVertex, b: &ClipVertex, t: f32) -> ClipVertex {
+        ClipVertex {
+            position: lerp_position(&a.position, &b.position, t),
+            varyings: Varyings::lerp(&a.varyings, &b.varyings, t),
+        }
+    }
+}
+
+/// A clipped triangle, kept on the stack since every face goes through here.
+struct ClipPolygon {
+    vertices: [ClipVertex; MAX_CLIPPED_VERTICES],
+    len: usize,
+}
+
+impl ClipPolygon {
+    fn new() -> ClipPolygon {
+        ClipPolygon {
+            vertices: [ClipVertex::default(); MAX_CLIPPED_VERTICES],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, vertex: ClipVertex) {
+        self.vertices[self.len] = vertex;
+        self.len += 1;
+    }
+
+    fn vertices(&self) -> &[ClipVertex] {
+        &self.vertices[..self.len]
+    }
+}
+
+/// Signed distances of a clip space position to the six view volume planes, positive inside.
+/// The near and far planes are exact, while the side planes are pushed out to the guard band.
+fn plane_distances(position: &Vec4<f32>) -> [f32; 6] {
+    let Vec4 { x, y, z, w } = *position;
+    let band = GUARD_BAND * w;
+
+    [w + z, w - z, band + x, band - x, band + y, band - y]
+}
+
+fn is_inside_view_volume(vertex: &ClipVertex) -> bool {
+    plane_distances(&vertex.position).iter().all(|&d| d >= 0.0)
+}
+
+fn lerp_position(a: &Vec4<f32>, b: &Vec4<f32>, t: f32) -> Vec4<f32> {
+    Vec4::new(
+        a.x + (b.x - a.x) * t,
+        a.y + (b.y - a.y) * t,
+        a.z + (b.z - a.z) * t,
+        a.w + (b.w - a.w) * t,
+    )
+}
+
+/// Clips a triangle against the view volume with Sutherland-Hodgman, one plane at a time.
+/// Positions and varyings are interpolated linearly, which is correct in clip space. Returns
+/// fewer than 3 vertices when nothing is left.
+fn clip_triangle(triangle: &[ClipVertex; 3]) -> ClipPolygon {
+    let mut polygon = ClipPolygon::new();
+    for vertex in triangle {
+        polygon.push(*vertex);
+    }
+
+    // most triangles are entirely inside, so skip the copying for them
+    if triangle.iter().all(is_inside_view_volume) {
+        return polygon;
+    }
+
+    for plane in 0..6 {
+        let input = polygon;
+        polygon = ClipPolygon::new();
+
+        for (i, current) in input.vertices().iter().enumerate() {
+            let previous = &input.vertices()[(i + input.len - 1) % input.len];
+            let d_previous = plane_distances(&previous.position)[plane];
+            let d_current = plane_distances(&current.position)[plane];
+
+            if (d_previous >= 0.0) != (d_current >= 0.0) {
+                let t = d_previous / (d_previous - d_current);
+                polygon.push(ClipVertex::lerp(previous, current, t));
+            }
+            if d_current >= 0.0 {
+                polygon.push(*current);
+            }
+        }
+
+        if polygon.len < 3 {
+            break;
+        }
+    }
+
+    polygon
+}
+
+/// Clips a segment against the view volume by narrowing its parametric range plane by plane.
+fn clip_line(a: &Vec4<f32>, b: &Vec4<f32>) -> Option<(Vec4<f32>, Vec4<f32>)> {
+    let (d_a, d_b) = (plane_distances(a), plane_distances(b));
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+
+    for plane in 0..6 {
+        let (da, db) = (d_a[plane], d_b[plane]);
+
+        if da < 0.0 && db < 0.0 {
+            return None;
+        } else if da < 0.0 {
+            t0 = t0.max(da / (da - db));
+        } else if db < 0.0 {
+            t1 = t1.min(da / (da - db));
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    Some((lerp_position(a, b, t0), lerp_position(a, b, t1)))
+}
+
+/// Applies the perspective divide and then the viewport transform to a clip space position.
+fn clip_to_screen_space(
+    viewport: &Mat4,
+    clip_coordinates: &Vec4<f32>,
+    varyings: Varyings,
+) -> ScreenVertex {
+    let ndc = clip_coordinates.perspective_divide();
+    let screen_coordinates = viewport * &Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+
+    ScreenVertex {
+        x: screen_coordinates.x,
+        y: screen_coordinates.y,
+        z: screen_coordinates.z,
+        inv_w: 1.0 / clip_coordinates.w,
+        varyings,
+    }
+}
+
+/// Whether a screen space polygon faces away from what `cull_mode` keeps. The sign of its
+/// doubled area tells the winding: positive is counter clockwise. Degenerate polygons are never
+/// culled.
+fn is_culled(cull_mode: CullMode, front_face: FrontFace, polygon: &[ScreenVertex]) -> bool {
+    let signed_area: f32 = (0..polygon.len())
+        .map(|i| {
+            let (a, b) = (&polygon[i], &polygon[(i + 1) % polygon.len()]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    if signed_area == 0.0 {
+        return false;
+    }
+
+    let is_front = match front_face {
+        FrontFace::CounterClockwise => signed_area > 0.0,
+        FrontFace::Clockwise => signed_area < 0.0,
+    };
+
+    match cull_mode {
+        CullMode::None => false,
+        CullMode::Back => !is_front,
+        CullMode::Front => is_front,
+    }
+}
+
+/// The corners of the triangles in a fan around the first corner of a face. Meshes from OBJ files
+/// are already triangulated, but this keeps faces built by hand from being read past their end.
+fn fan(face: &[FaceElement]) -> impl Iterator<Item = [&FaceElement; 3]> {
+    (2..face.len()).map(move |i| [&face[0], &face[i - 1], &face[i]])
+}
+
+/// The average position of a face's corners. Invalid corners are left out, as `face_inputs`
+/// reports them.
+fn face_center(mesh: &Mesh, face: &[FaceElement]) -> Vec4<f32> {
+    let positions = face
+        .iter()
+        .filter_map(|element| lookup(&mesh.vertices, element.vertex_index));
+
+    let (sum, count) = positions.fold((Vec4::new(0.0, 0.0, 0.0, 0.0), 0), |(sum, count), p| {
+        (&sum + &p, count + 1)
+    });
+    let scale = 1.0 / count.max(1) as f32;
+    Vec4::new(sum.x * scale, sum.y * scale, sum.z * scale, sum.w * scale)
+}
+
+/// Looks up the attributes of one of a face's triangles and gives them its normal and the
+/// face's `center`.
+fn face_inputs(
+    mesh: &Mesh,
+    corners: [&FaceElement; 3],
+    center: Vec4<f32>,
+) -> Result<[VertexInput; 3], Box<dyn Error>> {
+    let mut inputs = [
+        vertex_input(mesh, corners[0])?,
+        vertex_input(mesh, corners[1])?,
+        vertex_input(mesh, corners[2])?,
+    ];
+
+    let [a, b, c] = inputs.map(|input| {
+        let Vec4 { x, y, z, .. } = input.position;
+        Vec3::new(x, y, z)
+    });
+    let face_normal = (&b - &a).cross(&(&c - &a)).normalized();
+    for input in &mut inputs {
+        input.face_normal = face_normal;
+        input.face_center = center;
+    }
+
+    Ok(inputs)
+}
+
+/// Looks up the attributes a face element points at. A missing or invalid position is an error,
+/// while missing normals and texture coordinates are left for the shader to deal with.
+fn vertex_input(mesh: &Mesh, element: &FaceElement) -> Result<VertexInput, Box<dyn Error>> {
+    let position = element
+        .vertex_index
+        .ok_or("Face missing vertex index")?
+        .try_into()
+        .ok()
+        .and_then(|i: usize| mesh.vertices.get(i))
+        .ok_or("Invalid vertex index")?;
+
+    Ok(VertexInput {
+        position: *position,
+        normal: lookup(&mesh.vertex_normals, element.normal_index),
+        texture_coordinate: lookup(&mesh.texture_coordinates, element.texture_index),
+        face_normal: Vec3::new(0.0, 0.0, 0.0),
+        face_center: *position,
+    })
+}
+
+fn lookup<V: Copy>(values: &[V], index: Option<i32>) -> Option<V> {
+    let index: usize = index?.try_into().ok()?;
+    values.get(index).copied()
+}
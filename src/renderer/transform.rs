@@ -0,0 +1,36 @@
+use crate::geometry::{Mat4, Vec3};
+
+/// Placement of a mesh in the world. Applied at draw time as scale, then rotation, then
+/// translation, so the mesh's own vertex data is never modified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3<f32>,
+    /// Euler angles in radians, applied around x, then y, then z.
+    pub rotation: Vec3<f32>,
+    pub scale: Vec3<f32>,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::new()
+    }
+}
+
+impl Transform {
+    pub fn new() -> Transform {
+        Transform {
+            translation: Vec3::new(0.0, 0.0, 0.0),
+            rotation: Vec3::new(0.0, 0.0, 0.0),
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn to_matrix(&self) -> Mat4 {
+        let translation =
+            Mat4::translation(self.translation.x, self.translation.y, self.translation.z);
+        let rotation = Mat4::rotation_euler(self.rotation.x, self.rotation.y, self.rotation.z);
+        let scale = Mat4::scale(self.scale.x, self.scale.y, self.scale.z);
+
+        &(&translation * &rotation) * &scale
+    }
+}
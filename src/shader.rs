@@ -0,0 +1,383 @@
+use crate::geometry::{Mat3, Mat4, Vec3, Vec4};
+use crate::graphics::{Texture, color};
+use crate::lighting::{Illumination, Light, Material, illuminate};
+
+/// The most values a vertex shader can pass on to the fragment shader.
+pub const MAX_VARYINGS: usize = 16;
+
+/// Values output per vertex by `Shader::vertex` and interpolated across the triangle for
+/// `Shader::fragment`, read back in the order they were pushed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Varyings {
+    values: [f32; MAX_VARYINGS],
+    len: usize,
+}
+
+impl Default for Varyings {
+    fn default() -> Varyings {
+        Varyings::new()
+    }
+}
+
+impl Varyings {
+    pub fn new() -> Varyings {
+        Varyings {
+            values: [0.0; MAX_VARYINGS],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        &self.values[..self.len]
+    }
+
+    /// Panics if more than `MAX_VARYINGS` values are pushed.
+    pub fn push(&mut self, value: f32) {
+        if self.len == MAX_VARYINGS {
+            panic!("Error In Shader: More than {} varyings.", MAX_VARYINGS);
+        }
+
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+
+    pub fn push_vec3(&mut self, value: &Vec3<f32>) {
+        self.push(value.x);
+        self.push(value.y);
+        self.push(value.z);
+    }
+
+    pub fn get(&self, index: usize) -> f32 {
+        self.as_slice()[index]
+    }
+
+    /// Reads three consecutive values starting at `index`.
+    pub fn vec3(&self, index: usize) -> Vec3<f32> {
+        Vec3::new(self.get(index), self.get(index + 1), self.get(index + 2))
+    }
+
+    /// Blends three sets of varyings with barycentric weights.
+    pub fn interpolate(
+        v0: &Varyings,
+        v1: &Varyings,
+        v2: &Varyings,
+        w0: f32,
+        w1: f32,
+        w2: f32,
+    ) -> Varyings {
+        let mut result = Varyings {
+            values: [0.0; MAX_VARYINGS],
+            len: v0.len,
+        };
+
+        for i in 0..v0.len {
+            result.values[i] = v0.values[i] * w0 + v1.values[i] * w1 + v2.values[i] * w2;
+        }
+
+        result
+    }
+
+    /// Blends two sets of varyings, giving `a` at `t = 0` and `b` at `t = 1`.
+    pub fn lerp(a: &Varyings, b: &Varyings, t: f32) -> Varyings {
+        Varyings::interpolate(a, b, b, 1.0 - t, t, 0.0)
+    }
+}
+
+/// Per-vertex attributes looked up from a mesh face.
+#[derive(Debug, Clone, Copy)]
+pub struct VertexInput {
+    pub position: Vec4<f32>,
+    pub normal: Option<Vec3<f32>>,
+    pub texture_coordinate: Option<Vec3<f32>>,
+    /// The unit normal of the face the vertex belongs to, in object space, from its winding.
+    /// Zero for degenerate faces.
+    pub face_normal: Vec3<f32>,
+    /// The average of the face's corner positions, in object space.
+    pub face_center: Vec4<f32>,
+}
+
+/// Values that stay the same for every vertex and fragment of a mesh.
+#[derive(Debug, Clone, Copy)]
+pub struct Uniforms<'a> {
+    pub model: Mat4,
+    pub view: Mat4,
+    pub projection: Mat4,
+    pub model_view_projection: Mat4,
+    /// Inverse transpose of the model matrix, for taking normals into world space.
+    pub normal_matrix: Mat3,
+    pub camera_position: Vec3<f32>,
+    /// The diffuse color of the face's MTL material, or the drawing color for faces without one.
+    pub color: color::RGBA,
+    pub diffuse_texture: Option<&'a Texture>,
+    /// The renderer's lights, in world space.
+    pub lights: &'a [Light],
+    pub ambient_light: color::RGBA,
+    pub material: Material,
+}
+
+/// A programmable pipeline stage pair. The renderer calls `vertex` for each corner of a face and
+/// `fragment` for each pixel the face covers that passes the depth test.
+pub trait Shader {
+    /// Returns the clip space position of the vertex and pushes whatever the fragment stage
+    /// needs into `varyings`.
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32>;
+
+    /// Returns the color of a pixel from the interpolated varyings, or `None` to discard it.
+    fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA>;
+}
+
+/// Fills every pixel with `Uniforms::color`. `Uniforms::diffuse_texture` is ignored, so meshes
+/// with a texture, from `set_texture` or an MTL material, draw in their flat color.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ColorShader;
+
+impl Shader for ColorShader {
+    fn vertex(&self, input: &VertexInput, uniforms: &Uniforms, _: &mut Varyings) -> Vec4<f32> {
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, _: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+        Some(uniforms.color)
+    }
+}
+
+/// Colors each pixel by its interpolated world space normal, which is handy for checking normals.
+/// Faces without normals are drawn black.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalShader;
+
+impl Shader for NormalShader {
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32> {
+        let normal = input.normal.unwrap_or(Vec3::new(0.0, 0.0, 0.0));
+        varyings.push_vec3(&(&uniforms.normal_matrix * &normal).normalized());
+
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, varyings: &Varyings, _: &Uniforms) -> Option<color::RGBA> {
+        let normal = varyings.vec3(0).normalized();
+        let channel = |value: f32| ((value * 0.5 + 0.5) * 255.0).round() as u8;
+
+        if normal.length() == 0.0 {
+            return Some(color::RGBA {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            });
+        }
+
+        Some(color::RGBA {
+            r: channel(normal.x),
+            g: channel(normal.y),
+            b: channel(normal.z),
+            a: 255,
+        })
+    }
+}
+
+/// Samples `Uniforms::diffuse_texture` with the vertex texture coordinates. Falls back to
+/// `Uniforms::color` for meshes without a texture.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextureShader;
+
+impl Shader for TextureShader {
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32> {
+        let uv = input.texture_coordinate.unwrap_or(Vec3::new(0.0, 0.0, 0.0));
+        varyings.push(uv.x);
+        varyings.push(uv.y);
+
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+        match uniforms.diffuse_texture {
+            Some(texture) => Some(texture.sample(varyings.get(0), varyings.get(1))),
+            None => Some(uniforms.color),
+        }
+    }
+}
+
+/// Lights each face once, at its center with its face normal, so every corner carries the same
+/// light and the face gets a single shade. The diffuse color is the texture when the mesh has
+/// one, otherwise `Uniforms::color`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FlatShader;
+
+impl Shader for FlatShader {
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32> {
+        let center = &uniforms.model * &input.face_center;
+        let illumination = illuminate(
+            uniforms.lights,
+            &uniforms.ambient_light,
+            &uniforms.material,
+            &Vec3::new(center.x, center.y, center.z),
+            &(&uniforms.normal_matrix * &input.face_normal).normalized(),
+            &uniforms.camera_position,
+        );
+        push_illumination(&illumination, input, varyings);
+
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+        Some(illuminated_fragment(varyings, uniforms))
+    }
+}
+
+/// Lights each vertex with its normal, falling back to the face normal, and interpolates the
+/// light across the face.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GouraudShader;
+
+impl Shader for GouraudShader {
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32> {
+        let illumination = illuminate(
+            uniforms.lights,
+            &uniforms.ambient_light,
+            &uniforms.material,
+            &world_position(input, uniforms),
+            &world_normal(input, uniforms),
+            &uniforms.camera_position,
+        );
+        push_illumination(&illumination, input, varyings);
+
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+        Some(illuminated_fragment(varyings, uniforms))
+    }
+}
+
+/// Lights each pixel with the interpolated vertex normal, falling back to the face normal, and
+/// Blinn-Phong specular highlights.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PhongShader;
+
+impl Shader for PhongShader {
+    fn vertex(
+        &self,
+        input: &VertexInput,
+        uniforms: &Uniforms,
+        varyings: &mut Varyings,
+    ) -> Vec4<f32> {
+        varyings.push_vec3(&world_normal(input, uniforms));
+        push_surface(input, uniforms, varyings);
+
+        &uniforms.model_view_projection * &input.position
+    }
+
+    fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+        Some(lit_fragment(varyings, uniforms))
+    }
+}
+
+fn world_position(input: &VertexInput, uniforms: &Uniforms) -> Vec3<f32> {
+    let position = &uniforms.model * &input.position;
+    Vec3::new(position.x, position.y, position.z)
+}
+
+fn world_normal(input: &VertexInput, uniforms: &Uniforms) -> Vec3<f32> {
+    let normal = input.normal.unwrap_or(input.face_normal);
+    (&uniforms.normal_matrix * &normal).normalized()
+}
+
+fn push_texture_coordinate(input: &VertexInput, varyings: &mut Varyings) {
+    let uv = input.texture_coordinate.unwrap_or(Vec3::new(0.0, 0.0, 0.0));
+    varyings.push(uv.x);
+    varyings.push(uv.y);
+}
+
+/// Pushes the world position and texture coordinate that follow the normal for the per-pixel
+/// lit shaders.
+fn push_surface(input: &VertexInput, uniforms: &Uniforms, varyings: &mut Varyings) {
+    varyings.push_vec3(&world_position(input, uniforms));
+    push_texture_coordinate(input, varyings);
+}
+
+/// Pushes the light worked out in the vertex stage and the texture coordinate, for the shaders
+/// that don't light each pixel.
+fn push_illumination(illumination: &Illumination, input: &VertexInput, varyings: &mut Varyings) {
+    varyings.push_vec3(&illumination.diffuse);
+    varyings.push_vec3(&illumination.specular);
+    push_texture_coordinate(input, varyings);
+}
+
+/// Applies the light laid out by `push_illumination` to the pixel's base color.
+fn illuminated_fragment(varyings: &Varyings, uniforms: &Uniforms) -> color::RGBA {
+    let illumination = Illumination {
+        diffuse: varyings.vec3(0),
+        specular: varyings.vec3(3),
+    };
+
+    illumination.apply(&base_color(varyings, 6, uniforms))
+}
+
+/// Lights a pixel from a normal, world position and texture coordinate laid out by
+/// `push_surface`.
+fn lit_fragment(varyings: &Varyings, uniforms: &Uniforms) -> color::RGBA {
+    let illumination = illuminate(
+        uniforms.lights,
+        &uniforms.ambient_light,
+        &uniforms.material,
+        &varyings.vec3(3),
+        &varyings.vec3(0).normalized(),
+        &uniforms.camera_position,
+    );
+
+    illumination.apply(&base_color(varyings, 6, uniforms))
+}
+
+/// The diffuse texture at the texture coordinate starting at `index`, or `Uniforms::color`.
+fn base_color(varyings: &Varyings, index: usize, uniforms: &Uniforms) -> color::RGBA {
+    match uniforms.diffuse_texture {
+        Some(texture) => texture.sample(varyings.get(index), varyings.get(index + 1)),
+        None => uniforms.color,
+    }
+}
+
+/// A vertex after the vertex stage, perspective divide and viewport transform, ready for the
+/// rasterizer. `x` and `y` are in pixels and `z` is the depth buffer value.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenVertex {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    /// 1 / w of the clip space position, for perspective-correct interpolation.
+    pub inv_w: f32,
+    pub varyings: Varyings,
+}
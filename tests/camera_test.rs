@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_3};
+
+    use tiny_renderer::{
+        geometry::{Vec3, Vec4},
+        renderer::{Camera, Projection},
+    };
+
+    fn assert_vec3_near(a: &Vec3<f32>, b: &Vec3<f32>) {
+        assert!((a - b).length() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn default_camera_shows_unit_cube() {
+        let camera = Camera::default();
+        let view_projection = &camera.projection_matrix(1.0) * &camera.view_matrix();
+
+        let corner = (&view_projection * &Vec4::new(1.0, -1.0, 0.5, 1.0)).perspective_divide();
+        assert_vec3_near(&corner, &Vec3::new(1.0, -1.0, -0.5));
+    }
+
+    #[test]
+    fn orbit_keeps_distance_to_target() {
+        let mut camera = Camera::perspective(
+            Vec3::new(0.0, 0.0, 3.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            FRAC_PI_3,
+        );
+
+        camera.orbit(FRAC_PI_2, 0.0);
+        assert_vec3_near(&camera.position, &Vec3::new(4.0, 0.0, 1.0));
+
+        camera.orbit(0.3, 0.4);
+        assert!(((&camera.position - &camera.target).length() - 10f32.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn zoom_and_fly() {
+        let mut camera = Camera::perspective(
+            Vec3::new(0.0, 0.0, 4.0),
+            Vec3::new(0.0, 0.0, 0.0),
+            FRAC_PI_3,
+        );
+
+        camera.zoom(0.5);
+        assert_vec3_near(&camera.position, &Vec3::new(0.0, 0.0, 2.0));
+
+        camera.fly(1.0, 1.0, 0.0);
+        assert_vec3_near(&camera.position, &Vec3::new(1.0, 0.0, 1.0));
+        assert_vec3_near(&camera.target, &Vec3::new(1.0, 0.0, -1.0));
+
+        let mut orthographic =
+            Camera::orthographic(Vec3::new(0.0, 0.0, 4.0), Vec3::new(0.0, 0.0, 0.0), 2.0);
+        orthographic.zoom(2.0);
+        assert_eq!(
+            orthographic.projection,
+            Projection::Orthographic { height: 4.0 }
+        );
+    }
+}
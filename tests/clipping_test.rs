@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::{Vec3, Vec4},
+        graphics::{Framebuffer, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{Camera, DrawType, TinyRenderer, TinyRendererBuilder},
+        shader::{Shader, Uniforms, Varyings, VertexInput},
+    };
+
+    const SIZE: usize = 60;
+
+    /// Shades red by the object space x coordinate mapped from [-10, 10] to [0, 1].
+    struct PositionShader;
+
+    impl Shader for PositionShader {
+        fn vertex(
+            &self,
+            input: &VertexInput,
+            uniforms: &Uniforms,
+            varyings: &mut Varyings,
+        ) -> Vec4<f32> {
+            varyings.push((input.position.x + 10.0) / 20.0);
+            &uniforms.model_view_projection * &input.position
+        }
+
+        fn fragment(&self, varyings: &Varyings, _: &Uniforms) -> Option<color::RGBA> {
+            Some(color::RGBA {
+                r: (varyings.get(0) * 255.0).round() as u8,
+                g: 0,
+                b: 0,
+                a: 255,
+            })
+        }
+    }
+
+    fn triangle(vertices: [Vec4<f32>; 3]) -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vertices.to_vec();
+        mesh.faces = vec![
+            (0..3)
+                .map(|i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect(),
+        ];
+        mesh
+    }
+
+    fn renderer(draw_type: DrawType, mesh: Mesh) -> TinyRenderer<Framebuffer> {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(SIZE, SIZE))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::GREEN)
+            .build();
+
+        let id = renderer.add_mesh(mesh);
+        renderer.set_draw_type(id, draw_type);
+        renderer
+    }
+
+    fn count_lit(renderer: &TinyRenderer<Framebuffer>) -> usize {
+        let framebuffer = renderer.render_output();
+        framebuffer
+            .pixels()
+            .iter()
+            .filter(|pixel| pixel.a != 0)
+            .count()
+    }
+
+    #[test]
+    fn clips_triangles_reaching_behind_the_camera() {
+        // a floor running from in front of the camera to behind it
+        let floor = triangle([
+            Vec4::new(-2.0, -1.0, -5.0, 1.0),
+            Vec4::new(2.0, -1.0, -5.0, 1.0),
+            Vec4::new(0.0, -1.0, 5.0, 1.0),
+        ]);
+
+        for draw_type in [DrawType::Fill, DrawType::Line] {
+            let mut renderer = renderer(draw_type, floor.clone());
+            renderer.set_camera(Camera::perspective(
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(0.0, 0.0, -1.0),
+                std::f32::consts::FRAC_PI_2,
+            ));
+            renderer.draw().unwrap();
+
+            assert_eq!(renderer.stats().drawn_faces, 1);
+
+            // the floor is below the horizon, so nothing may land in the top half
+            let framebuffer = renderer.render_output();
+            for y in SIZE / 2 + 1..SIZE {
+                assert!(framebuffer.row(y).iter().all(|pixel| pixel.a == 0));
+            }
+            assert!(count_lit(&renderer) > 0);
+        }
+
+        let mut renderer = renderer(DrawType::Fill, floor);
+        renderer.set_camera(Camera::perspective(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            std::f32::consts::FRAC_PI_2,
+        ));
+        renderer.draw().unwrap();
+        assert_eq!(renderer.render_output().get(30, 2), Some(color::GREEN));
+    }
+
+    #[test]
+    fn discards_faces_outside_the_view_volume() {
+        // entirely behind the far plane of the default camera
+        let mut renderer = renderer(
+            DrawType::Fill,
+            triangle([
+                Vec4::new(-0.5, -0.5, -3.0, 1.0),
+                Vec4::new(0.5, -0.5, -3.0, 1.0),
+                Vec4::new(0.0, 0.5, -3.0, 1.0),
+            ]),
+        );
+        renderer.draw().unwrap();
+
+        assert_eq!(renderer.stats().clipped_faces, 1);
+        assert_eq!(renderer.stats().drawn_faces, 0);
+        assert_eq!(count_lit(&renderer), 0);
+    }
+
+    #[test]
+    fn interpolates_varyings_across_clipped_triangles() {
+        // far bigger than the guard band, so every side gets clipped
+        let mut renderer = renderer(
+            DrawType::Fill,
+            triangle([
+                Vec4::new(-10.0, -10.0, 0.0, 1.0),
+                Vec4::new(10.0, -10.0, 0.0, 1.0),
+                Vec4::new(0.0, 10.0, 0.0, 1.0),
+            ]),
+        );
+        renderer.set_shader(0, PositionShader);
+        renderer.draw().unwrap();
+
+        assert_eq!(count_lit(&renderer), SIZE * SIZE);
+
+        // the left edge of the screen is x = -1 and the right edge x = 1
+        let framebuffer = renderer.render_output();
+        for (x, expected) in [(0, 115.0), (30, 128.0), (59, 140.0)] {
+            let red = framebuffer.get(x, 30).unwrap().r as f32;
+            assert!((red - expected).abs() <= 1.0, "{} at x = {}", red, x);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec4,
+        graphics::{Framebuffer, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{CullMode, DrawStats, DrawType, FrontFace, TinyRenderer, TinyRendererBuilder},
+    };
+
+    // a counter clockwise triangle on the left and a clockwise one on the right
+    fn two_triangle_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-0.9, -0.5, 0.0, 1.0),
+            Vec4::new(-0.1, -0.5, 0.0, 1.0),
+            Vec4::new(-0.5, 0.5, 0.0, 1.0),
+            Vec4::new(0.1, -0.5, 0.0, 1.0),
+            Vec4::new(0.5, 0.5, 0.0, 1.0),
+            Vec4::new(0.9, -0.5, 0.0, 1.0),
+        ];
+
+        let face = |indices: [i32; 3]| {
+            indices
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face([0, 1, 2]), face([3, 4, 5])];
+        mesh
+    }
+
+    fn draw(cull_mode: CullMode, front_face: FrontFace) -> TinyRenderer<Framebuffer> {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(50, 50))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::GREEN)
+            .build();
+        renderer.set_front_face(front_face);
+
+        let id = renderer.add_mesh(two_triangle_mesh());
+        renderer.set_draw_type(id, DrawType::Fill);
+        renderer.set_cull_mode(id, cull_mode);
+        renderer.draw().unwrap();
+        renderer
+    }
+
+    // whether the left (counter clockwise) and right (clockwise) triangles were drawn
+    fn drawn(renderer: &TinyRenderer<Framebuffer>) -> (bool, bool) {
+        let framebuffer = renderer.render_output();
+        (
+            framebuffer.get(12, 20) == Some(color::GREEN),
+            framebuffer.get(37, 20) == Some(color::GREEN),
+        )
+    }
+
+    #[test]
+    fn culls_by_screen_space_winding() {
+        let renderer = draw(CullMode::None, FrontFace::CounterClockwise);
+        assert_eq!(drawn(&renderer), (true, true));
+        assert_eq!(
+            renderer.stats(),
+            DrawStats {
+                drawn_faces: 2,
+                culled_faces: 0,
+                clipped_faces: 0,
+            }
+        );
+
+        let renderer = draw(CullMode::Back, FrontFace::CounterClockwise);
+        assert_eq!(drawn(&renderer), (true, false));
+        assert_eq!(renderer.stats().culled_faces, 1);
+
+        let renderer = draw(CullMode::Front, FrontFace::CounterClockwise);
+        assert_eq!(drawn(&renderer), (false, true));
+        assert_eq!(renderer.stats().culled_faces, 1);
+    }
+
+    #[test]
+    fn clockwise_front_face_flips_culling() {
+        let renderer = draw(CullMode::Back, FrontFace::Clockwise);
+        assert_eq!(drawn(&renderer), (false, true));
+        assert_eq!(
+            renderer.stats(),
+            DrawStats {
+                drawn_faces: 1,
+                culled_faces: 1,
+                clipped_faces: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn polygons_count_as_one_face() {
+        let mut mesh = two_triangle_mesh();
+        // the clockwise triangle becomes a clockwise quad, fanned into two triangles
+        mesh.vertices.push(Vec4::new(0.9, 0.5, 0.0, 1.0));
+        mesh.faces[1] = [3, 4, 6, 5]
+            .iter()
+            .map(|&i| FaceElement {
+                vertex_index: Some(i),
+                texture_index: None,
+                normal_index: None,
+            })
+            .collect();
+
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(50, 50))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build();
+        let id = renderer.add_mesh(mesh);
+        renderer.set_cull_mode(id, CullMode::Back);
+        renderer.draw().unwrap();
+
+        assert_eq!(
+            renderer.stats(),
+            DrawStats {
+                drawn_faces: 1,
+                culled_faces: 1,
+                clipped_faces: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::{
+            Algorithms, RasterizeTriangleAlg, bresenhams_line_alg, rasterize_triangle,
+            rasterize_triangle_scanline,
+        },
+        geometry::{Vec3, Vec4},
+        graphics::{RenderTarget, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{DrawType, TinyRendererBuilder},
+    };
+
+    const WIDTH: usize = 100;
+    const HEIGHT: usize = 100;
+
+    struct MemoryTarget {
+        pixels: Vec<(u8, u8, u8)>,
+    }
+
+    impl RenderTarget for MemoryTarget {
+        fn width(&self) -> usize {
+            WIDTH
+        }
+
+        fn height(&self) -> usize {
+            HEIGHT
+        }
+
+        fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+            if x >= 0 && y >= 0 && (x as usize) < WIDTH && (y as usize) < HEIGHT {
+                self.pixels[x as usize + y as usize * WIDTH] = (color.r, color.g, color.b);
+            }
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    fn square_mesh(z: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-0.5, -0.5, z, 1.0),
+            Vec4::new(0.5, -0.5, z, 1.0),
+            Vec4::new(0.5, 0.5, z, 1.0),
+            Vec4::new(-0.5, 0.5, z, 1.0),
+        ];
+
+        let face = |a: i32, b: i32, c: i32| {
+            [a, b, c]
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face(0, 1, 2), face(0, 2, 3)];
+        mesh
+    }
+
+    fn render_overlapping_squares(rasterize_triangle_alg: RasterizeTriangleAlg<MemoryTarget>) {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(MemoryTarget {
+                pixels: vec![(0, 0, 0); WIDTH * HEIGHT],
+            })
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle_alg))
+            .with_color(color::GREEN)
+            .build();
+
+        // the front square is added first, so without depth testing the back one would win
+        let front = renderer.add_mesh(square_mesh(0.5));
+        renderer.set_draw_type(front, DrawType::Fill);
+        renderer.draw().unwrap();
+
+        let back = renderer.add_mesh(square_mesh(-0.5));
+        renderer.set_draw_type(back, DrawType::Fill);
+        renderer.draw().unwrap();
+
+        let depth_buffer = renderer.depth_buffer();
+        // z = 0.5 in the default [-1, 1] view volume maps to a depth of 0.75
+        assert_eq!(depth_buffer.get(50, 50), Some(0.75));
+        assert_eq!(depth_buffer.get(5, 5), Some(f32::NEG_INFINITY));
+        assert_eq!(depth_buffer.get(-1, 5), None);
+    }
+
+    #[test]
+    fn rasterize_triangle_keeps_closest_fragment() {
+        render_overlapping_squares(rasterize_triangle);
+    }
+
+    #[test]
+    fn rasterize_triangle_scanline_keeps_closest_fragment() {
+        render_overlapping_squares(rasterize_triangle_scanline);
+    }
+
+    #[test]
+    fn clearing_depth_lets_the_next_draw_through() {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(MemoryTarget {
+                pixels: vec![(0, 0, 0); WIDTH * HEIGHT],
+            })
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build();
+
+        let id = renderer.add_mesh(square_mesh(0.5));
+        renderer.set_draw_type(id, DrawType::Fill);
+        renderer.draw().unwrap();
+
+        // moved back to z = -0.5, it is hidden by its own last frame until depth is cleared
+        renderer.set_translation(id, Vec3::new(0.0, 0.0, -1.0));
+        renderer.draw().unwrap();
+        assert_eq!(renderer.depth_buffer().get(50, 50), Some(0.75));
+
+        renderer.clear_depth();
+        assert_eq!(renderer.depth_buffer().get(50, 50), Some(f32::NEG_INFINITY));
+        renderer.draw().unwrap();
+        assert_eq!(renderer.depth_buffer().get(50, 50), Some(0.25));
+    }
+
+    #[test]
+    fn depth_buffer_dumps_as_grayscale() {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(MemoryTarget {
+                pixels: vec![(0, 0, 0); WIDTH * HEIGHT],
+            })
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build();
+
+        let id = renderer.add_mesh(square_mesh(0.25));
+        renderer.set_draw_type(id, DrawType::Fill);
+        renderer.draw().unwrap();
+
+        let mut dump = MemoryTarget {
+            pixels: vec![(7, 7, 7); WIDTH * HEIGHT],
+        };
+        renderer.depth_buffer().draw_grayscale(&mut dump);
+
+        // z = 0.25 maps to a depth of 0.625
+        assert_eq!(dump.pixels[50 + 50 * WIDTH], (159, 159, 159));
+        assert_eq!(dump.pixels[5 + 5 * WIDTH], (0, 0, 0));
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use tiny_renderer::mesh::{FlatMesh, Mesh, MeshLoadError, NO_INDEX, ParseMode};
+
+    const BODY_OBJ: &str = "tests/obj/body.obj";
+
+    // an L shaped hexagon, whose fan from the first corner would leave the shape
+    const OBJ: &str = "v 0 0 0
+v 2 0 0
+v 2 1 0
+v 1 1 0
+v 1 2 0
+v 0 2 0
+vt 0 0
+vn 0 0 1
+f 3 4 5 6 1 2
+f -6/1 -5/-1 -4//1
+";
+
+    #[test]
+    fn faces_become_flat_triangle_indices() {
+        let (mesh, warnings) = FlatMesh::from_obj_str(OBJ, ParseMode::Strict).unwrap();
+        assert!(warnings.is_empty());
+
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.triangle_count(), 5);
+        // ear clipping leaves every triangle of the hexagon touching its reflex corner, vertex 4
+        assert_eq!(
+            mesh.vertex_indices[..12],
+            [1, 2, 3, 3, 4, 5, 3, 5, 0, 3, 0, 1]
+        );
+        assert_eq!(mesh.vertex_indices[12..], [0, 1, 2]);
+        assert_eq!(mesh.texture_indices[12..], [0, 0, NO_INDEX]);
+        assert_eq!(mesh.normal_indices[12..], [NO_INDEX, NO_INDEX, 0]);
+
+        let reader = BufReader::with_capacity(16, OBJ.as_bytes());
+        let (from_reader, _) = FlatMesh::from_obj_reader(reader, ParseMode::Strict).unwrap();
+        assert_eq!(from_reader, mesh);
+    }
+
+    #[test]
+    fn problems_are_reported_like_the_mesh_loader() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 2\nf 1 2 4\nf 1 2\nf 1 2 3\n";
+        let (mesh, warnings) = FlatMesh::from_obj_str(obj, ParseMode::Lenient).unwrap();
+        assert_eq!(mesh.triangle_count(), 1);
+
+        let locations: Vec<_> = warnings
+            .iter()
+            .map(|warning| match warning {
+                MeshLoadError::Parse {
+                    line,
+                    column,
+                    message,
+                    ..
+                } => (*line, *column, message.as_str()),
+                _ => panic!("{:?} is not a parse error", warning),
+            })
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                (4, 6, "missing vertex z"),
+                (5, 7, "invalid vertex index \"4\""),
+                (6, 6, "missing face corners, a face needs at least 3"),
+            ]
+        );
+
+        assert!(FlatMesh::from_obj_str(obj, ParseMode::Strict).is_err());
+        let missing = FlatMesh::from_obj_file("no/such/file.obj", ParseMode::Lenient);
+        assert!(matches!(missing, Err(MeshLoadError::Io { .. })));
+    }
+
+    #[test]
+    fn body_matches_the_mesh_loader() {
+        let mesh = Mesh::from_obj_file(BODY_OBJ).unwrap();
+        let (flat, _) = FlatMesh::from_obj_file(BODY_OBJ, ParseMode::Lenient).unwrap();
+        assert_eq!(flat.triangle_count(), mesh.faces.len());
+
+        let converted = Mesh::from(flat);
+        assert_eq!(converted.vertices, mesh.vertices);
+        assert_eq!(converted.vertex_normals, mesh.vertex_normals);
+        assert_eq!(converted.texture_coordinates, mesh.texture_coordinates);
+        for (converted, face) in converted.faces.iter().zip(&mesh.faces) {
+            for (a, b) in converted.iter().zip(face) {
+                assert_eq!(
+                    (a.vertex_index, a.texture_index, a.normal_index),
+                    (b.vertex_index, b.texture_index, b.normal_index)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn both_loaders_read_the_same_lines() {
+        // tabs, leading whitespace and comments after the corners
+        let obj = "v\t0 0 0\n  v 1 0 0\nv 1\t1 0\t1\nvt\t0.5\nvn 0 0 1\nf\t1/1 2/1 3//1 # tri\n";
+        let path = std::env::temp_dir().join("tiny_renderer_whitespace.obj");
+        std::fs::write(&path, obj).unwrap();
+
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+        let (flat, _) = FlatMesh::from_obj_str(obj, ParseMode::Strict).unwrap();
+        assert_eq!(mesh.vertices, flat.vertices);
+        assert_eq!(mesh.texture_coordinates, flat.texture_coordinates);
+        assert_eq!(mesh.vertex_normals, flat.vertex_normals);
+        assert_eq!(mesh.faces.len(), 1);
+        assert_eq!(flat.vertex_indices, [0, 1, 2]);
+        assert_eq!(flat.texture_indices, [0, 0, NO_INDEX]);
+    }
+}
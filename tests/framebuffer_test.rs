@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec4,
+        graphics::{Framebuffer, RenderTarget, color, png, tga},
+        mesh::{FaceElement, Mesh},
+        renderer::{DrawType, TinyRendererBuilder},
+    };
+
+    fn triangle_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-0.5, -0.5, 0.0, 1.0),
+            Vec4::new(0.5, -0.5, 0.0, 1.0),
+            Vec4::new(0.0, 0.5, 0.0, 1.0),
+        ];
+        mesh.faces = vec![
+            (0..3)
+                .map(|i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect(),
+        ];
+        mesh
+    }
+
+    #[test]
+    fn reads_back_rendered_pixels() {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(40, 40))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::RED)
+            .build();
+
+        let id = renderer.add_mesh(triangle_mesh());
+        renderer.set_draw_type(id, DrawType::Fill);
+        renderer.draw().unwrap();
+
+        let framebuffer = renderer.render_output();
+        assert_eq!(framebuffer.get(20, 15), Some(color::RED));
+        assert_eq!(
+            framebuffer.get(2, 38),
+            Some(color::RGBA {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0
+            })
+        );
+        assert_eq!(framebuffer.get(40, 0), None);
+        assert_eq!(framebuffer.get(0, -1), None);
+
+        let row = framebuffer.row(15);
+        assert_eq!(row.len(), 40);
+        assert_eq!(row[20], color::RED);
+        assert_eq!(framebuffer.pixels().len(), 40 * 40);
+    }
+
+    #[test]
+    fn clears_and_edits_rows() {
+        let mut framebuffer = Framebuffer::new(3, 2);
+        framebuffer.clear(&color::BLUE);
+        assert!(
+            framebuffer
+                .pixels()
+                .iter()
+                .all(|pixel| *pixel == color::BLUE)
+        );
+
+        framebuffer.row_mut(1)[2] = color::GREEN;
+        assert_eq!(framebuffer.get(2, 1), Some(color::GREEN));
+        assert_eq!(framebuffer.row(0), &[color::BLUE; 3]);
+    }
+
+    #[test]
+    fn copies_into_file_encoders() {
+        let mut framebuffer = Framebuffer::new(4, 4);
+        framebuffer.set(1, 3, &color::PURPLE);
+
+        let mut tga_image = tga::Image::new(
+            "unused.tga",
+            4,
+            4,
+            tga::ImageType::UncompressedTrueColor,
+            tga::ColorType::RGBA,
+        );
+        framebuffer.copy_to(&mut tga_image);
+        assert_eq!(tga_image.get(1, 3), Some(color::PURPLE));
+
+        // read back through the trait, the way a boxed target would be
+        let mut png_image: Box<dyn RenderTarget> =
+            Box::new(png::Image::new("unused.png", 4, 4, png::ColorType::RGB));
+        framebuffer.copy_to(&mut png_image);
+        assert_eq!(png_image.get(1, 3), Some(color::PURPLE));
+        assert_eq!(png_image.get(0, 0).map(|pixel| pixel.a), Some(255));
+    }
+
+    #[test]
+    fn targets_without_read_back_return_none() {
+        struct WriteOnly;
+
+        impl RenderTarget for WriteOnly {
+            fn width(&self) -> usize {
+                1
+            }
+
+            fn height(&self) -> usize {
+                1
+            }
+
+            fn set(&mut self, _x: i32, _y: i32, _color: &color::RGBA) {}
+
+            fn render(&mut self) -> Result<(), Box<dyn Error>> {
+                Ok(())
+            }
+        }
+
+        let mut target = WriteOnly;
+        target.set(0, 0, &color::RED);
+        assert_eq!(target.get(0, 0), None);
+    }
+}
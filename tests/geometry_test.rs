@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use tiny_renderer::geometry::{Mat3, Mat4, Vec3, Vec4};
+
+    fn assert_vec4_near(a: &Vec4<f32>, b: &Vec4<f32>) {
+        let close = |x: f32, y: f32| (x - y).abs() < 1e-4;
+        assert!(
+            close(a.x, b.x) && close(a.y, b.y) && close(a.z, b.z) && close(a.w, b.w),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    fn assert_mat4_near(a: &Mat4, b: &Mat4) {
+        for row in 0..4 {
+            for col in 0..4 {
+                assert!(
+                    (a.m[row][col] - b.m[row][col]).abs() < 1e-4,
+                    "{:?} != {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_transform() {
+        let transform = &(&Mat4::translation(1.0, -2.0, 3.0)
+            * &Mat4::rotation_euler(0.3, 0.7, -1.1))
+            * &Mat4::scale(2.0, 0.5, 4.0);
+        let inverse = transform.inverse().unwrap();
+
+        assert_mat4_near(&(&transform * &inverse), &Mat4::identity());
+        assert_mat4_near(&(&inverse * &transform), &Mat4::identity());
+        assert!(Mat4::scale(1.0, 0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn mat3_inverse_and_transpose() {
+        let mat = Mat3::from_mat4(&Mat4::rotation_euler(0.2, -0.4, 1.3));
+        let inverse = mat.inverse().unwrap();
+
+        // rotations are orthonormal, so the inverse is the transpose
+        for row in 0..3 {
+            for col in 0..3 {
+                assert!((inverse.m[row][col] - mat.transpose().m[row][col]).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn small_scales_are_invertible() {
+        let transform = &Mat4::rotation_euler(0.2, -0.4, 1.3) * &Mat4::scale(0.001, 0.001, 0.001);
+        let inverse = transform.inverse().unwrap();
+        assert_mat4_near(&(&transform * &inverse), &Mat4::identity());
+
+        let mat = Mat3::from_mat4(&transform);
+        let product = &mat * &mat.inverse().unwrap();
+        for row in 0..3 {
+            for col in 0..3 {
+                let expected = if row == col { 1.0 } else { 0.0 };
+                assert!((product.m[row][col] - expected).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn rotation_follows_right_hand_rule() {
+        let rotated = &Mat4::rotation_z(FRAC_PI_2) * &Vec4::new(1.0, 0.0, 0.0, 1.0);
+        assert_vec4_near(&rotated, &Vec4::new(0.0, 1.0, 0.0, 1.0));
+
+        let axis_angle =
+            &Mat4::rotation(&Vec3::new(0.0, 0.0, 2.0), FRAC_PI_2) * &Vec4::new(1.0, 0.0, 0.0, 1.0);
+        assert_vec4_near(&axis_angle, &rotated);
+    }
+
+    #[test]
+    fn look_at_moves_target_in_front_of_camera() {
+        let view = Mat4::look_at(
+            &Vec3::new(0.0, 0.0, 5.0),
+            &Vec3::new(0.0, 0.0, 0.0),
+            &Vec3::new(0.0, 1.0, 0.0),
+        );
+
+        let target = &view * &Vec4::new(0.0, 0.0, 0.0, 1.0);
+        assert_vec4_near(&target, &Vec4::new(0.0, 0.0, -5.0, 1.0));
+    }
+
+    #[test]
+    fn projections_map_near_and_far_planes() {
+        let perspective = Mat4::perspective(FRAC_PI_2, 1.0, 1.0, 10.0);
+        let near = (&perspective * &Vec4::new(0.0, 0.0, -1.0, 1.0)).perspective_divide();
+        let far = (&perspective * &Vec4::new(0.0, 0.0, -10.0, 1.0)).perspective_divide();
+        assert!((near.z + 1.0).abs() < 1e-4);
+        assert!((far.z - 1.0).abs() < 1e-4);
+
+        let orthographic = Mat4::orthographic(-2.0, 2.0, -1.0, 1.0, 1.0, 3.0);
+        let corner = &orthographic * &Vec4::new(2.0, -1.0, -3.0, 1.0);
+        assert_vec4_near(&corner, &Vec4::new(1.0, -1.0, 1.0, 1.0));
+
+        let viewport = Mat4::viewport(0.0, 0.0, 100.0, 50.0);
+        let pixel = &viewport * &Vec4::new(1.0, -1.0, -1.0, 1.0);
+        assert_vec4_near(&pixel, &Vec4::new(100.0, 0.0, 1.0, 1.0));
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        graphics::{Framebuffer, color},
+        mesh::{FaceGroup, GroupKind, Mesh, NormalWeighting},
+        renderer::{GroupStyle, TinyRenderer, TinyRendererBuilder},
+    };
+
+    fn load(name: &str, obj: &str) -> Mesh {
+        let path = std::env::temp_dir().join(format!("tiny_renderer_{}.obj", name));
+        fs::write(&path, obj).unwrap();
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        mesh
+    }
+
+    // a quad on the left, a quad on the right and a triangle along the top, all facing the camera
+    const OBJ: &str = "v -1 -1 0
+v 0 -1 0
+v 0 0.8 0
+v -1 0.8 0
+v 1 -1 0
+v 1 0.8 0
+v 0 1 0
+o panel
+g left shared
+s 1
+f 1 2 3 4
+g right shared
+s off
+f 2 5 6 3
+g
+s 2
+f 4 6 7
+";
+
+    fn group(name: &str, kind: GroupKind, start: usize, end: usize) -> FaceGroup {
+        FaceGroup {
+            name: name.to_string(),
+            kind,
+            faces: start..end,
+        }
+    }
+
+    #[test]
+    fn objects_and_groups_become_face_ranges() {
+        let mesh = load("groups", OBJ);
+
+        assert_eq!(
+            mesh.groups,
+            vec![
+                group("left", GroupKind::Group, 0, 2),
+                group("shared", GroupKind::Group, 0, 2),
+                group("right", GroupKind::Group, 2, 4),
+                group("shared", GroupKind::Group, 2, 4),
+                group("default", GroupKind::Group, 4, 5),
+                group("panel", GroupKind::Object, 0, 5),
+            ]
+        );
+        assert_eq!(
+            mesh.group_names(),
+            vec!["left", "shared", "right", "default", "panel"]
+        );
+        assert_eq!(
+            mesh.group_faces("shared").collect::<Vec<_>>(),
+            vec![0..2, 2..4]
+        );
+
+        // every triangle of a split quad keeps the quad's smoothing group
+        assert_eq!(mesh.smoothing_groups, vec![1, 1, 0, 0, 2]);
+
+        let without_smoothing = load("no_smoothing", "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n");
+        assert!(without_smoothing.smoothing_groups.is_empty());
+        assert!(without_smoothing.groups.is_empty());
+    }
+
+    // two triangles folded 10 degrees along the edge from vertex 1 to vertex 2
+    const FOLD: &str = "v 0 0 0
+v 1 0 0
+v 0.5 1 0
+v 0.5 -0.985 0.174
+";
+
+    #[test]
+    fn normals_are_only_shared_within_a_smoothing_group() {
+        let normal_count = |smoothing: [&str; 2]| {
+            let obj = format!(
+                "{}s {}\nf 1 2 3\ns {}\nf 2 1 4\n",
+                FOLD, smoothing[0], smoothing[1]
+            );
+            let mut mesh = load("smoothing", &obj);
+            mesh.generate_normals(NormalWeighting::Area, None);
+            mesh.vertex_normals.len()
+        };
+
+        assert_eq!(normal_count(["1", "1"]), 4);
+        assert_eq!(normal_count(["1", "2"]), 6);
+        assert_eq!(normal_count(["off", "off"]), 6);
+    }
+
+    fn draw(configure: impl Fn(&mut TinyRenderer<Framebuffer>, usize)) -> Framebuffer {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(20, 20))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::RED)
+            .build();
+        let id = renderer.add_mesh(load("group_render", OBJ));
+        configure(&mut renderer, id);
+        renderer.draw().unwrap();
+
+        renderer.render_output().clone()
+    }
+
+    #[test]
+    fn groups_can_be_hidden_and_recolored_by_name() {
+        let all = draw(|_, _| {});
+        assert_eq!(all.get(5, 5), Some(color::RED));
+        assert_eq!(all.get(15, 5), Some(color::RED));
+
+        let recolored = draw(|renderer, id| {
+            renderer.set_group_color(id, "right", Some(color::BLUE));
+            renderer.set_group_color(id, "panel", Some(color::GREEN));
+            renderer.set_group_visible(id, "left", false);
+        });
+        assert_eq!(recolored.get(5, 5), Framebuffer::new(20, 20).get(5, 5));
+        // the object ends after the group, so its color wins
+        assert_eq!(recolored.get(15, 5), Some(color::GREEN));
+
+        let shown_again = draw(|renderer, id| {
+            renderer.set_group_color(id, "right", Some(color::BLUE));
+            renderer.set_group_visible(id, "left", false);
+            renderer.set_group_visible(id, "left", true);
+            assert_eq!(
+                renderer.group_style(id, "right"),
+                GroupStyle {
+                    visible: true,
+                    color: Some(color::BLUE),
+                }
+            );
+        });
+        assert_eq!(shown_again.get(5, 5), Some(color::RED));
+        assert_eq!(shown_again.get(15, 5), Some(color::BLUE));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid group")]
+    fn unknown_groups_panic() {
+        draw(|renderer, id| renderer.set_group_visible(id, "missing", false));
+    }
+}
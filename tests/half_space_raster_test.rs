@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::{
+            rasterize_half_space, rasterize_triangle_scanline, triangle_raster::MAX_COORDINATE,
+        },
+        geometry::{Mat3, Mat4, Rect, Vec3},
+        graphics::{DepthBuffer, RenderTarget, color},
+        lighting::Material,
+        shader::{ColorShader, ScreenVertex, Uniforms, Varyings},
+    };
+
+    const SIZE: usize = 64;
+
+    struct CoverageTarget {
+        covered: Vec<bool>,
+    }
+
+    impl RenderTarget for CoverageTarget {
+        fn width(&self) -> usize {
+            SIZE
+        }
+
+        fn height(&self) -> usize {
+            SIZE
+        }
+
+        fn set(&mut self, x: i32, y: i32, _color: &color::RGBA) {
+            self.covered[x as usize + y as usize * SIZE] = true;
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    /// A small deterministic generator, so failures can be reproduced.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self, min: f32, max: f32) -> f32 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            min + (max - min) * ((self.0 >> 40) as f32 / (1u64 << 24) as f32)
+        }
+    }
+
+    fn scanline_coverage(p0: (f32, f32), p1: (f32, f32), p2: (f32, f32)) -> Vec<bool> {
+        let vertex = |(x, y): (f32, f32)| ScreenVertex {
+            x,
+            y,
+            z: 0.5,
+            inv_w: 1.0,
+            varyings: Varyings::new(),
+        };
+        let uniforms = Uniforms {
+            model: Mat4::identity(),
+            view: Mat4::identity(),
+            projection: Mat4::identity(),
+            model_view_projection: Mat4::identity(),
+            normal_matrix: Mat3::identity(),
+            camera_position: Vec3::new(0.0, 0.0, 0.0),
+            color: color::GREEN,
+            diffuse_texture: None,
+            lights: &[],
+            ambient_light: color::RGBA {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            material: Material::default(),
+        };
+
+        let mut target = CoverageTarget {
+            covered: vec![false; SIZE * SIZE],
+        };
+        rasterize_triangle_scanline(
+            &vertex(p0),
+            &vertex(p1),
+            &vertex(p2),
+            &ColorShader,
+            &uniforms,
+            &mut DepthBuffer::new(SIZE, SIZE),
+            &mut target,
+        );
+        target.covered
+    }
+
+    #[test]
+    fn matches_scanline_coverage() {
+        let mut rng = Lcg(7);
+        let bounds = Rect::from_size(SIZE, SIZE);
+
+        for i in 0..500 {
+            // some triangles reach off screen, and every tenth has vertices on pixel centers
+            let mut point = || {
+                let (x, y) = (rng.next(-10.0, 74.0), rng.next(-10.0, 74.0));
+                if i % 10 == 0 {
+                    (x.floor() + 0.5, y.floor() + 0.5)
+                } else {
+                    (x, y)
+                }
+            };
+            let (p0, p1, p2) = (point(), point(), point());
+
+            let mut covered = vec![false; SIZE * SIZE];
+            rasterize_half_space(p0, p1, p2, &bounds, |x, y, _| {
+                let pixel = &mut covered[x as usize + y as usize * SIZE];
+                assert!(!*pixel, "pixel {}, {} visited twice", x, y);
+                *pixel = true;
+            });
+
+            assert!(
+                covered == scanline_coverage(p0, p1, p2),
+                "coverage differs for {:?}",
+                (p0, p1, p2)
+            );
+        }
+    }
+
+    #[test]
+    fn barycentrics_reconstruct_pixel_centers() {
+        let (p0, p1, p2) = ((3.2, 4.7), (50.1, 12.9), (20.6, 60.3));
+
+        // both windings report weights in the order the vertices were given
+        for (a, b, c) in [(p0, p1, p2), (p0, p2, p1)] {
+            let mut visited = 0;
+            rasterize_half_space(
+                a,
+                b,
+                c,
+                &Rect::from_size(SIZE, SIZE),
+                |x, y, (w0, w1, w2)| {
+                    visited += 1;
+                    assert!(w0 >= -1e-4 && w1 >= -1e-4 && w2 >= -1e-4);
+                    assert!((w0 + w1 + w2 - 1.0).abs() < 1e-4);
+
+                    let px = w0 * a.0 + w1 * b.0 + w2 * c.0;
+                    let py = w0 * a.1 + w1 * b.1 + w2 * c.1;
+                    assert!((px - (x as f32 + 0.5)).abs() < 0.01);
+                    assert!((py - (y as f32 + 0.5)).abs() < 0.01);
+                },
+            );
+            assert!(visited > 500);
+        }
+    }
+
+    #[test]
+    fn stays_inside_the_bounds() {
+        let bounds = Rect::new(10, 20, 17, 23);
+        let mut visited = 0;
+
+        rasterize_half_space(
+            (-100.0, -100.0),
+            (200.0, -100.0),
+            (50.0, 300.0),
+            &bounds,
+            |x, y, _| {
+                assert!(bounds.contains(x, y));
+                visited += 1;
+            },
+        );
+
+        assert_eq!(visited, 8 * 4);
+    }
+
+    #[test]
+    fn skips_triangles_beyond_the_coordinate_limit() {
+        let bounds = Rect::new(0, 0, SIZE as i32 - 1, SIZE as i32 - 1);
+        let count = |far: f32| {
+            let mut visited = 0;
+            rasterize_half_space((0.0, 0.0), (far, 0.0), (0.0, far), &bounds, |_, _, _| {
+                visited += 1
+            });
+            visited
+        };
+
+        assert_eq!(count(MAX_COORDINATE / 2.0), SIZE * SIZE);
+        assert_eq!(count(MAX_COORDINATE), 0);
+
+        let scanline = scanline_coverage((0.0, 0.0), (MAX_COORDINATE, 0.0), (0.0, 10.0));
+        assert!(scanline.iter().all(|&covered| !covered));
+    }
+}
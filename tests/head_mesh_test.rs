@@ -4,6 +4,7 @@ mod tests {
 
     use tiny_renderer::{
         algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec3,
         graphics::{
             color,
             tga::{self},
@@ -15,18 +16,18 @@ mod tests {
     const WIDTH: u16 = 5000;
     const HEIGHT: u16 = 5000;
 
-    const HEAD_OBJ: &'static str = "tests/obj/head.obj";
-    const BODY_OBJ: &'static str = "tests/obj/body.obj";
-    const MESH_CREATED_BY_TEST: &'static str = "tests/output/meshes_created_by_test.tga";
-    const MESH_FILE: &'static str = "tests/output/meshes.tga";
+    const HEAD_OBJ: &str = "tests/obj/head.obj";
+    const BODY_OBJ: &str = "tests/obj/body.obj";
+    const MESH_CREATED_BY_TEST: &str = "tests/output/meshes_created_by_test.tga";
+    const MESH_FILE: &str = "tests/output/meshes.tga";
 
     fn create_mesh_image() {
         // create a renderer with a tga image output instead of a window
         let mut renderer = TinyRendererBuilder::new()
             .with_render_output(tga::Image::new(
                 MESH_CREATED_BY_TEST,
-                WIDTH as u16,
-                HEIGHT as u16,
+                WIDTH,
+                HEIGHT,
                 tga::ImageType::UncompressedTrueColor,
                 tga::ColorType::RGB,
             ))
@@ -38,24 +39,21 @@ mod tests {
             panic!("Error reading in the mesh: {}", err);
         });
 
-        let body_mesh_2 = body_mesh.clone();
-        let body_mesh_3 = body_mesh.clone();
-
         // bring in more meshes!
         let body_id = renderer.add_mesh(body_mesh);
         renderer.set_draw_type(body_id, DrawType::Line);
-        renderer.scale_vertices(body_id, 0.05);
-        renderer.move_vertices(body_id, 0.0, -1.2);
+        renderer.set_scale(body_id, Vec3::new(0.05, 0.05, 0.05));
+        renderer.set_translation(body_id, Vec3::new(0.0, -1.2, 0.0));
 
-        let body_id_2 = renderer.add_mesh(body_mesh_2);
+        let body_id_2 = renderer.add_mesh_instance(body_id);
         renderer.set_draw_type(body_id_2, DrawType::Line);
-        renderer.scale_vertices(body_id_2, 0.1);
-        renderer.move_vertices(body_id_2, -1.0, -1.0);
+        renderer.set_scale(body_id_2, Vec3::new(0.1, 0.1, 0.1));
+        renderer.set_translation(body_id_2, Vec3::new(-1.0, -1.0, 0.0));
 
-        let body_id_3 = renderer.add_mesh(body_mesh_3);
+        let body_id_3 = renderer.add_mesh_instance(body_id);
         renderer.set_draw_type(body_id_3, DrawType::Line);
-        renderer.scale_vertices(body_id_3, 0.1);
-        renderer.move_vertices(body_id_3, 1.0, -1.0);
+        renderer.set_scale(body_id_3, Vec3::new(0.1, 0.1, 0.1));
+        renderer.set_translation(body_id_3, Vec3::new(1.0, -1.0, 0.0));
 
         // read in a mesh from our obj file
         let mesh = Mesh::from_obj_file(HEAD_OBJ).unwrap_or_else(|err| {
@@ -64,7 +62,7 @@ mod tests {
 
         let head_mesh_id = renderer.add_mesh(mesh);
         renderer.set_draw_type(head_mesh_id, DrawType::Line);
-        renderer.scale_vertices(head_mesh_id, 0.5);
+        renderer.set_scale(head_mesh_id, Vec3::new(0.5, 0.5, 0.5));
 
         // call our draw function once since it's just a single image
         if let Err(err) = renderer.draw() {
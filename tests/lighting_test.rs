@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::{Vec3, Vec4},
+        graphics::{Framebuffer, color},
+        lighting::{Attenuation, Light, Material, ShadingModel, illuminate},
+        mesh::{FaceElement, Mesh},
+        renderer::{TinyRenderer, TinyRendererBuilder},
+    };
+
+    const BLACK: color::RGBA = color::RGBA {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    fn assert_close(actual: Vec3<f32>, expected: (f32, f32, f32)) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            close(actual.x, expected.0)
+                && close(actual.y, expected.1)
+                && close(actual.z, expected.2),
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    // the diffuse light reaching the origin of a surface facing +z, lit only by `lights`
+    fn diffuse_at(lights: &[Light], position: Vec3<f32>) -> Vec3<f32> {
+        illuminate(
+            lights,
+            &BLACK,
+            &Material::default(),
+            &position,
+            &Vec3::new(0.0, 0.0, 1.0),
+            &Vec3::new(0.0, 0.0, 5.0),
+        )
+        .diffuse
+    }
+
+    #[test]
+    fn directional_lights_follow_lamberts_law_and_add_up() {
+        let origin = Vec3::new(0.0, 0.0, 0.0);
+        let head_on = Light::directional(Vec3::new(0.0, 0.0, -1.0), color::WHITE);
+        assert_close(diffuse_at(&[head_on], origin), (1.0, 1.0, 1.0));
+
+        // 60 degrees off the normal lets half the light through
+        let slanted = Light::directional(Vec3::new(3f32.sqrt(), 0.0, -1.0), color::WHITE);
+        assert_close(diffuse_at(&[slanted], origin), (0.5, 0.5, 0.5));
+
+        let behind = Light::directional(Vec3::new(0.0, 0.0, 1.0), color::WHITE);
+        assert_close(diffuse_at(&[behind], origin), (0.0, 0.0, 0.0));
+
+        let red = Light::directional(Vec3::new(0.0, 0.0, -1.0), color::RED);
+        let blue = Light::directional(Vec3::new(0.0, 0.0, -1.0), color::BLUE).with_intensity(0.5);
+        assert_close(diffuse_at(&[red, blue], origin), (1.0, 0.0, 0.5));
+    }
+
+    #[test]
+    fn point_and_spot_lights_fall_off() {
+        let point = Light::point(Vec3::new(0.0, 0.0, 2.0), color::WHITE)
+            .with_attenuation(Attenuation::new(1.0, 0.0, 1.0));
+        assert_close(
+            diffuse_at(&[point], Vec3::new(0.0, 0.0, 0.0)),
+            (0.2, 0.2, 0.2),
+        );
+
+        let spot = Light::spot(
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            0.2,
+            0.4,
+            color::WHITE,
+        );
+        assert_close(
+            diffuse_at(&[spot], Vec3::new(0.0, 0.0, 0.0)),
+            (1.0, 1.0, 1.0),
+        );
+        assert_close(
+            diffuse_at(&[spot], Vec3::new(1.0, 0.0, 0.0)),
+            (0.0, 0.0, 0.0),
+        );
+
+        // halfway between the inner and outer cone
+        let edge = diffuse_at(&[spot], Vec3::new(0.3f32.tan(), 0.0, 0.0)).x;
+        assert!(edge > 0.3 && edge < 0.6, "{}", edge);
+    }
+
+    #[test]
+    fn blinn_specular_peaks_when_the_half_vector_is_the_normal() {
+        let light = Light::directional(Vec3::new(0.0, 0.0, -1.0), color::WHITE);
+        let material = Material {
+            specular: color::WHITE,
+            shininess: 16.0,
+        };
+        let specular = |eye: Vec3<f32>| {
+            illuminate(
+                &[light],
+                &BLACK,
+                &material,
+                &Vec3::new(0.0, 0.0, 0.0),
+                &Vec3::new(0.0, 0.0, 1.0),
+                &eye,
+            )
+            .specular
+        };
+
+        assert_close(specular(Vec3::new(0.0, 0.0, 3.0)), (1.0, 1.0, 1.0));
+        assert!(specular(Vec3::new(3.0, 0.0, 3.0)).x < 0.5);
+    }
+
+    // a full screen quad at z = 0 facing the default camera, with vertex normals
+    fn lit_quad(
+        shading_model: ShadingModel,
+        light: Light,
+        material: Material,
+    ) -> TinyRenderer<Framebuffer> {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, 1.0, 0.0, 1.0),
+            Vec4::new(-1.0, 1.0, 0.0, 1.0),
+        ];
+        mesh.vertex_normals = vec![Vec3::new(0.0, 0.0, 1.0)];
+        let face = |indices: [i32; 3]| {
+            indices
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: Some(0),
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face([0, 1, 2]), face([0, 2, 3])];
+
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(64, 64))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::RGBA {
+                r: 200,
+                g: 200,
+                b: 200,
+                a: 255,
+            })
+            .build();
+        renderer.set_ambient_light(BLACK);
+        renderer.add_light(light);
+
+        let id = renderer.add_mesh(mesh);
+        renderer.set_shading_model(id, shading_model);
+        renderer.set_material(id, material);
+        renderer.draw().unwrap();
+        renderer
+    }
+
+    #[test]
+    fn phong_catches_highlights_that_gouraud_misses() {
+        let light = Light::point(Vec3::new(0.0, 0.0, 0.5), color::WHITE);
+        let phong = lit_quad(ShadingModel::Phong, light, Material::default());
+        let gouraud = lit_quad(ShadingModel::Gouraud, light, Material::default());
+        let red = |renderer: &TinyRenderer<Framebuffer>, x, y| {
+            renderer.render_output().get(x, y).unwrap().r
+        };
+
+        // the light is over the middle of the quad, far from every vertex
+        assert_eq!(red(&phong, 32, 32), 255);
+        assert!(red(&phong, 0, 0) < 120);
+        assert!(red(&gouraud, 32, 32) < 120);
+        assert!(red(&gouraud, 32, 32).abs_diff(red(&gouraud, 0, 0)) <= 2);
+    }
+
+    #[test]
+    fn flat_shading_gives_a_face_one_color() {
+        let light = Light::directional(Vec3::new(0.0, -1.0, -1.0), color::WHITE);
+        let material = Material {
+            specular: BLACK,
+            shininess: 1.0,
+        };
+        let flat = lit_quad(ShadingModel::Flat, light, material);
+
+        // 45 degrees off the face normal
+        let expected = (200.0 * std::f32::consts::FRAC_1_SQRT_2).round() as u8;
+        for pixel in flat.render_output().pixels() {
+            assert_eq!(pixel.r, expected);
+        }
+    }
+
+    #[test]
+    fn flat_shading_lights_a_face_once_under_point_lights() {
+        // close enough that lighting each pixel would vary a lot, as with phong above
+        let light = Light::point(Vec3::new(0.0, 0.0, 0.5), color::WHITE);
+        let flat = lit_quad(ShadingModel::Flat, light, Material::default());
+
+        // both triangles have their centers equally far from the light
+        let first = flat.render_output().pixels()[0];
+        assert_ne!(first, BLACK);
+        for pixel in flat.render_output().pixels() {
+            assert_eq!(*pixel, first);
+        }
+    }
+}
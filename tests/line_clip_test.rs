@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, clip_line, rasterize_triangle},
+        geometry::Rect,
+        graphics::{DepthBuffer, RenderTarget, color},
+        renderer::DrawingContext,
+    };
+
+    const WIDTH: usize = 40;
+    const HEIGHT: usize = 30;
+
+    /// Counts every `set`, including the ones that land off screen.
+    struct CountingTarget {
+        sets: usize,
+        outside: usize,
+    }
+
+    impl RenderTarget for CountingTarget {
+        fn width(&self) -> usize {
+            WIDTH
+        }
+
+        fn height(&self) -> usize {
+            HEIGHT
+        }
+
+        fn set(&mut self, x: i32, y: i32, _color: &color::RGBA) {
+            self.sets += 1;
+            if !Rect::from_size(WIDTH, HEIGHT).contains(x, y) {
+                self.outside += 1;
+            }
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    fn context() -> DrawingContext<CountingTarget> {
+        DrawingContext {
+            render_output: CountingTarget {
+                sets: 0,
+                outside: 0,
+            },
+            depth_buffer: DepthBuffer::new(WIDTH, HEIGHT),
+            algorithms: Algorithms::new(bresenhams_line_alg, rasterize_triangle),
+            color: color::GREEN,
+            scissor: None,
+        }
+    }
+
+    #[test]
+    fn rejects_segments_fully_outside() {
+        let rect = Rect::new(0, 0, 9, 9);
+
+        assert_eq!(clip_line(-5, 2, -1, 8, &rect), None);
+        assert_eq!(clip_line(2, 10, 8, 50, &rect), None);
+        // outside on different sides, but missing the corner
+        assert_eq!(clip_line(-3, 8, 1, 12, &rect), None);
+        assert_eq!(clip_line(0, 0, 5, 5, &Rect::new(3, 3, 2, 2)), None);
+    }
+
+    #[test]
+    fn clips_crossing_segments() {
+        let rect = Rect::new(0, 0, 9, 9);
+
+        // inside segments come back untouched
+        assert_eq!(clip_line(1, 2, 8, 7, &rect), Some((1, 2, 8, 7)));
+
+        assert_eq!(
+            clip_line(-1_000_000, 4, 1_000_000, 4, &rect),
+            Some((0, 4, 9, 4))
+        );
+        assert_eq!(clip_line(5, 20, 5, -20, &rect), Some((5, 9, 5, 0)));
+        assert_eq!(clip_line(-10, -10, 20, 20, &rect), Some((0, 0, 9, 9)));
+        // one endpoint inside
+        assert_eq!(clip_line(4, 4, 4, 100, &rect), Some((4, 4, 4, 9)));
+    }
+
+    #[test]
+    fn handles_degenerate_segments() {
+        let rect = Rect::new(0, 0, 9, 9);
+
+        assert_eq!(clip_line(3, 3, 3, 3, &rect), Some((3, 3, 3, 3)));
+        assert_eq!(clip_line(12, 3, 12, 3, &rect), None);
+        // touching the corner in a single pixel
+        assert_eq!(clip_line(-1, 1, 1, -1, &rect), Some((0, 0, 0, 0)));
+    }
+
+    #[test]
+    fn draw_line_only_visits_visible_pixels() {
+        let mut ctx = context();
+        ctx.draw_line(-1_000_000, 10, 1_000_000, 10);
+        assert_eq!(ctx.render_output.sets, WIDTH);
+        assert_eq!(ctx.render_output.outside, 0);
+
+        let mut ctx = context();
+        ctx.draw_line(-500, -500, -100, 5000);
+        assert_eq!(ctx.render_output.sets, 0);
+    }
+
+    #[test]
+    fn draw_line_respects_the_scissor_rect() {
+        let mut ctx = context();
+        ctx.scissor = Some(Rect::new(10, 0, 19, 29));
+        ctx.draw_line(0, 5, 39, 5);
+        assert_eq!(ctx.render_output.sets, 10);
+
+        // a scissor reaching past the target is cut to it
+        let mut ctx = context();
+        ctx.scissor = Some(Rect::new(30, -100, 100, 100));
+        ctx.draw_line(20, 0, 20, 29);
+        ctx.draw_line(35, -50, 35, 50);
+        assert_eq!(ctx.render_output.sets, HEIGHT);
+        assert_eq!(ctx.render_output.outside, 0);
+    }
+}
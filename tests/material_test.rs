@@ -0,0 +1,184 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec3,
+        graphics::{Framebuffer, RenderTarget, color, tga},
+        mesh::{MaterialRange, Mesh},
+        renderer::TinyRendererBuilder,
+        shader::TextureShader,
+    };
+
+    // two quads covering the left and right halves of the screen, the left one red and the right
+    // one blue from a texture, with a sliver at the top drawn before any usemtl
+    const OBJ: &str = "mtllib materials.mtl
+v -1 -1 0
+v 0 -1 0
+v 0 0.9 0
+v -1 0.9 0
+v 1 -1 0
+v 1 0.9 0
+v 1 1 0
+v -1 1 0
+vt 0.5 0.5
+f 4 8 7 6
+usemtl red
+f 1 2 3 4
+usemtl missing
+usemtl textured
+f 2/1 5/1 6/1 3/1
+";
+
+    const MTL: &str = "# two materials
+newmtl red
+Ka 0.1 0.1 0.1
+Kd 1 0 0
+Ks 0.5
+Ns 64
+d 0.5
+illum 1
+
+newmtl textured
+Kd 0 1 0
+map_Kd -clamp on textures/blue.tga
+map_Bump -bm 0.5 bump map.tga
+map_Ks -o 0.1 0.2 -mm 0 1 textures/shine.tga
+";
+
+    // writes the OBJ, MTL and texture into a directory of their own and returns the OBJ path
+    fn write_files(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("tiny_renderer_{}", name));
+        fs::create_dir_all(directory.join("textures")).unwrap();
+        fs::write(directory.join("model.obj"), OBJ).unwrap();
+        fs::write(directory.join("materials.mtl"), MTL).unwrap();
+
+        let mut texture = tga::Image::new(
+            "unused.tga",
+            1,
+            1,
+            tga::ImageType::UncompressedTrueColor,
+            tga::ColorType::RGBA,
+        );
+        texture.set(0, 0, &color::BLUE);
+        texture
+            .write_to_file(directory.join("textures/blue.tga").to_str().unwrap())
+            .unwrap();
+
+        directory.join("model.obj")
+    }
+
+    #[test]
+    fn mtl_files_are_read_next_to_the_obj() {
+        let path = write_files("mtl_parse");
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+        let directory = path.parent().unwrap();
+
+        let red = &mesh.materials[0];
+        assert_eq!(red.name, "red");
+        assert_eq!(red.ambient, Vec3::new(0.1, 0.1, 0.1));
+        assert_eq!(red.diffuse, Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(red.specular, Vec3::new(0.5, 0.5, 0.5));
+        assert_eq!((red.shininess, red.dissolve), (64.0, 0.5));
+        assert_eq!(red.illumination_model, 1);
+        assert_eq!(red.diffuse_color().a, 128);
+        assert_eq!(red.lighting_material().specular.r, 0);
+        assert!(red.diffuse_texture.is_none());
+
+        let textured = &mesh.materials[1];
+        assert_eq!(
+            textured.diffuse_map,
+            Some(directory.join("textures/blue.tga"))
+        );
+        assert_eq!(textured.bump_map, Some(directory.join("bump map.tga")));
+        assert_eq!(
+            textured.specular_map,
+            Some(directory.join("textures/shine.tga"))
+        );
+        assert_eq!(
+            textured.diffuse_texture.as_ref().unwrap().texel(0, 0),
+            color::BLUE
+        );
+
+        // the faces after the unknown material belong to the next usemtl
+        assert_eq!(
+            mesh.material_ranges,
+            vec![
+                MaterialRange {
+                    material: 0,
+                    faces: 2..4,
+                },
+                MaterialRange {
+                    material: 1,
+                    faces: 4..6,
+                },
+            ]
+        );
+        assert_eq!(
+            mesh.face_materials(),
+            vec![None, None, Some(0), Some(0), Some(1), Some(1)]
+        );
+    }
+
+    #[test]
+    fn triangulating_keeps_material_ranges_on_their_faces() {
+        let path = write_files("mtl_triangulate");
+        let mut mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+
+        // merge the triangles of every quad back together
+        mesh.faces = mesh
+            .faces
+            .chunks(2)
+            .map(|pair| {
+                let mut quad = pair[0].clone();
+                quad.push(pair[1][2].clone());
+                quad
+            })
+            .collect();
+        mesh.material_ranges = vec![
+            MaterialRange {
+                material: 0,
+                faces: 1..2,
+            },
+            MaterialRange {
+                material: 1,
+                faces: 2..3,
+            },
+        ];
+
+        mesh.triangulate();
+        assert_eq!(mesh.faces.len(), 6);
+        assert_eq!(mesh.material_ranges[0].faces, 2..4);
+        assert_eq!(mesh.material_ranges[1].faces, 4..6);
+    }
+
+    #[test]
+    fn faces_are_drawn_with_their_material() {
+        let path = write_files("mtl_render");
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(20, 20))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::GREEN)
+            .build();
+        let id = renderer.add_mesh(mesh);
+        renderer.set_shader(id, TextureShader);
+        renderer.draw().unwrap();
+
+        let output = renderer.render_output();
+        assert_eq!(
+            output.get(5, 10),
+            Some(color::RGBA {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 128,
+            })
+        );
+        assert_eq!(output.get(15, 10), Some(color::BLUE));
+        assert_eq!(output.get(10, 19), Some(color::GREEN));
+    }
+}
@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tiny_renderer::graphics::{RenderTarget, tga};
+    use tiny_renderer::mesh::{Mesh, MeshLoadError, MtlMaterial, ObjLoadOptions, ParseMode};
+
+    fn write(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn load(
+        name: &str,
+        obj: &str,
+        options: ObjLoadOptions,
+    ) -> Result<(Mesh, Vec<MeshLoadError>), MeshLoadError> {
+        let path = write(&format!("tiny_renderer_{}.obj", name), obj);
+        Mesh::load_obj(path.to_str().unwrap(), &options)
+    }
+
+    const STRICT: ObjLoadOptions = ObjLoadOptions {
+        mode: ParseMode::Strict,
+        triangulate: true,
+    };
+
+    const LENIENT: ObjLoadOptions = ObjLoadOptions {
+        mode: ParseMode::Lenient,
+        triangulate: true,
+    };
+
+    // (line, column, message) of a parse error
+    fn location(error: &MeshLoadError) -> (usize, usize, &str) {
+        match error {
+            MeshLoadError::Parse {
+                line,
+                column,
+                message,
+                ..
+            } => (*line, *column, message),
+            _ => panic!("{:?} is not a parse error", error),
+        }
+    }
+
+    #[test]
+    fn strict_mode_points_at_the_first_bad_token() {
+        let error = load("short_vertex", "v 0 0 0\nv 1 2\nv 1 1\n", STRICT).unwrap_err();
+        assert_eq!(location(&error), (2, 6, "missing vertex z"));
+
+        let error = load("bad_number", "v 0 0 0\nvt 0.5 x\n", STRICT).unwrap_err();
+        assert_eq!(
+            location(&error),
+            (2, 8, "invalid texture coordinate v \"x\"")
+        );
+        match &error {
+            MeshLoadError::Parse { file, text, .. } => {
+                assert!(file.ends_with("tiny_renderer_bad_number.obj"));
+                assert_eq!(text, "vt 0.5 x");
+            }
+            _ => unreachable!(),
+        }
+        assert!(
+            error
+                .to_string()
+                .ends_with("tiny_renderer_bad_number.obj:2:8: invalid texture coordinate v \"x\" in \"vt 0.5 x\""),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn faces_must_point_at_what_was_read_before_them() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 4\nf 1 -4 3\nf 1/1 2 3\nf 1 2\n";
+        let (mesh, warnings) = load("bad_faces", obj, LENIENT).unwrap();
+
+        assert!(mesh.faces.is_empty());
+        let locations: Vec<_> = warnings.iter().map(location).collect();
+        assert_eq!(
+            locations,
+            vec![
+                (4, 7, "invalid vertex index \"4\""),
+                (5, 5, "invalid vertex index \"-4\""),
+                (6, 5, "invalid texture coordinate index \"1\""),
+                (7, 6, "missing face corners, a face needs at least 3"),
+            ]
+        );
+
+        assert!(load("bad_faces_strict", obj, STRICT).is_err());
+    }
+
+    #[test]
+    fn lenient_mode_skips_bad_lines_and_keeps_the_rest() {
+        let obj = "mtllib missing.mtl
+v 0 0 0
+v 1 0
+v 1 0 0
+v 0 1 0
+s on
+usemtl nothing
+f 1 2 3
+";
+        let (mesh, warnings) = load("lenient", obj, LENIENT).unwrap();
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.faces.len(), 1);
+
+        assert_eq!(warnings.len(), 4);
+        assert!(matches!(warnings[0], MeshLoadError::Io { .. }));
+        assert_eq!(location(&warnings[1]), (3, 6, "missing vertex z"));
+        assert_eq!(
+            location(&warnings[2]),
+            (6, 3, "invalid smoothing group \"on\"")
+        );
+        assert_eq!(
+            location(&warnings[3]),
+            (7, 8, "unknown material \"nothing\"")
+        );
+
+        // the missing library and unknown material don't stop a strict load, the short vertex does
+        let error = load("strict_missing_library", obj, STRICT).unwrap_err();
+        assert_eq!(location(&error), (3, 6, "missing vertex z"));
+    }
+
+    #[test]
+    fn loading_is_strict_unless_asked_otherwise() {
+        let obj = "mtllib missing.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl nothing\nf 1 2 3\n";
+        let path = write("tiny_renderer_default_mode.obj", obj);
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(mesh.faces.len(), 1);
+
+        let (_, warnings) = load("default_mode", obj, ObjLoadOptions::default()).unwrap();
+        assert_eq!(warnings.len(), 2);
+
+        let path = write("tiny_renderer_default_mode_bad.obj", "v 0 0 0\nv 1 0\n");
+        let error = Mesh::from_obj_file(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(location(&error), (2, 6, "missing vertex z"));
+
+        let mtl = write("tiny_renderer_default_mode.mtl", "newmtl a\nKd 1 x 1\n");
+        assert!(MtlMaterial::from_mtl_file(&mtl).is_err());
+        let (materials, warnings) = MtlMaterial::load_mtl(&mtl, &LENIENT).unwrap();
+        assert_eq!((materials.len(), warnings.len()), (1, 1));
+    }
+
+    #[test]
+    fn material_problems_are_reported_against_the_mtl_file() {
+        let mtl = write(
+            "tiny_renderer_problems.mtl",
+            "Kd 1 1 1\nnewmtl broken\nKd 1 x 1\nmap_Kd missing_texture.tga\n",
+        );
+        let obj = "mtllib tiny_renderer_problems.mtl\nusemtl broken\n";
+        let (mesh, warnings) = load("mtl_problems", obj, LENIENT).unwrap();
+
+        assert_eq!(mesh.materials.len(), 1);
+        assert_eq!(location(&warnings[0]), (1, 1, "statement before newmtl"));
+        assert_eq!(location(&warnings[1]), (3, 6, "invalid number \"x\""));
+        match &warnings[0] {
+            MeshLoadError::Parse { file, .. } => assert_eq!(file, &mtl),
+            _ => unreachable!(),
+        }
+        match &warnings[2] {
+            MeshLoadError::Texture { file, .. } => {
+                assert!(file.ends_with("missing_texture.tga"))
+            }
+            error => panic!("{:?} is not a texture error", error),
+        }
+        assert_eq!(warnings.len(), 3);
+    }
+
+    #[test]
+    fn empty_texture_maps_are_reported() {
+        let texture = std::env::temp_dir().join("tiny_renderer_empty_map.tga");
+        tga::Image::new(
+            texture.to_str().unwrap(),
+            0,
+            0,
+            tga::ImageType::UncompressedTrueColor,
+            tga::ColorType::RGB,
+        )
+        .render()
+        .unwrap();
+        write(
+            "tiny_renderer_empty_map.mtl",
+            "newmtl empty\nmap_Kd tiny_renderer_empty_map.tga\n",
+        );
+
+        let obj = "mtllib tiny_renderer_empty_map.mtl\nusemtl empty\n";
+        let (mesh, warnings) = load("empty_map", obj, LENIENT).unwrap();
+        assert!(mesh.materials[0].diffuse_texture.is_none());
+        assert!(matches!(warnings[..], [MeshLoadError::Texture { .. }]));
+
+        let error = load("empty_map_strict", obj, STRICT).unwrap_err();
+        assert!(matches!(error, MeshLoadError::Texture { .. }));
+    }
+
+    #[test]
+    fn maps_that_are_not_tga_are_left_untextured() {
+        write("tiny_renderer_photo_map.png", "\u{89}PNG\r\n");
+        write(
+            "tiny_renderer_photo_map.mtl",
+            "newmtl photo\nmap_Kd tiny_renderer_photo_map.png\nnewmtl scan\nmap_Kd scan.JPG\n",
+        );
+
+        let obj = "mtllib tiny_renderer_photo_map.mtl\nusemtl photo\n";
+        let (mesh, warnings) = load("photo_map", obj, STRICT).unwrap();
+        assert!(warnings.is_empty());
+        for material in &mesh.materials {
+            assert!(material.diffuse_map.is_some());
+            assert!(material.diffuse_texture.is_none());
+        }
+    }
+
+    #[test]
+    fn options_can_keep_polygons_whole() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n";
+        let options = ObjLoadOptions::new()
+            .with_mode(ParseMode::Strict)
+            .with_triangulate(false);
+        let (mesh, warnings) = load("polygons", obj, options).unwrap();
+
+        assert_eq!(mesh.faces.len(), 1);
+        assert_eq!(mesh.faces[0].len(), 4);
+        assert!(warnings.is_empty());
+
+        let missing = Mesh::from_obj_file("no/such/file.obj").unwrap_err();
+        assert!(matches!(missing, MeshLoadError::Io { .. }));
+    }
+}
@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        geometry::{Vec3, Vec4},
+        mesh::{FaceElement, Mesh, NormalWeighting},
+    };
+
+    fn face(indices: &[i32]) -> Vec<FaceElement> {
+        indices
+            .iter()
+            .map(|&i| FaceElement {
+                vertex_index: Some(i),
+                texture_index: None,
+                normal_index: None,
+            })
+            .collect()
+    }
+
+    // a unit cube around the origin, with each side either a quad or split into two triangles
+    fn cube(triangulated: bool) -> Mesh {
+        let mut mesh = Mesh::new();
+        for i in 0..8 {
+            let coordinate = |bit: i32| if i & bit == 0 { -0.5 } else { 0.5 };
+            mesh.vertices
+                .push(Vec4::new(coordinate(1), coordinate(2), coordinate(4), 1.0));
+        }
+
+        let sides = [
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+        ];
+        for [a, b, c, d] in sides {
+            if triangulated {
+                mesh.faces.push(face(&[a, b, c]));
+                mesh.faces.push(face(&[a, c, d]));
+            } else {
+                mesh.faces.push(face(&[a, b, c, d]));
+            }
+        }
+        mesh
+    }
+
+    fn normal_at(mesh: &Mesh, face: usize, corner: usize) -> Vec3<f32> {
+        let index = mesh.faces[face][corner].normal_index.unwrap() as usize;
+        mesh.vertex_normals[index]
+    }
+
+    // the normal of the first corner found at `vertex`
+    fn normal_at_vertex(mesh: &Mesh, vertex: i32) -> Vec3<f32> {
+        for (f, face) in mesh.faces.iter().enumerate() {
+            if let Some(corner) = face.iter().position(|e| e.vertex_index == Some(vertex)) {
+                return normal_at(mesh, f, corner);
+            }
+        }
+        panic!("No corner at vertex {}", vertex);
+    }
+
+    fn assert_close(actual: Vec3<f32>, expected: Vec3<f32>) {
+        let difference = &actual - &expected;
+        assert!(
+            difference.length() < 1e-5,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn face_normals_follow_the_winding() {
+        let mut mesh = cube(false);
+        mesh.faces.push(face(&[0, 1, 0]));
+
+        let normals = mesh.face_normals();
+        assert_close(normals[0], Vec3::new(-1.0, 0.0, 0.0));
+        assert_close(normals[1], Vec3::new(1.0, 0.0, 0.0));
+        assert_close(normals[5], Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(normals[6], Vec3::new(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn smooth_normals_point_out_of_the_corners() {
+        // vertex 5 is the corner at (0.5, -0.5, 0.5)
+        let diagonal = Vec3::new(1.0, -1.0, 1.0).normalized();
+
+        // angle weighting doesn't care how the sides were split
+        for triangulated in [false, true] {
+            let mut mesh = cube(triangulated);
+            mesh.generate_normals(NormalWeighting::Angle, None);
+
+            assert_eq!(mesh.vertex_normals.len(), 8);
+            assert_close(normal_at_vertex(&mesh, 5), diagonal);
+        }
+
+        // area weighting leans towards the -y side, which has both its triangles at the corner
+        let mut mesh = cube(true);
+        mesh.generate_normals(NormalWeighting::Area, None);
+        assert_close(
+            normal_at_vertex(&mesh, 5),
+            Vec3::new(0.5, -1.0, 0.5).normalized(),
+        );
+    }
+
+    #[test]
+    fn crease_angle_splits_hard_edges() {
+        let mut mesh = cube(true);
+        mesh.generate_normals(NormalWeighting::Angle, Some(30f32.to_radians()));
+
+        // three sides meet at every corner
+        assert_eq!(mesh.vertex_normals.len(), 24);
+
+        let face_normals = mesh.face_normals();
+        for (f, face) in mesh.faces.iter().enumerate() {
+            for corner in 0..face.len() {
+                assert_close(normal_at(&mesh, f, corner), face_normals[f]);
+            }
+        }
+    }
+
+    #[test]
+    fn shallow_folds_stay_smooth() {
+        // two triangles folded 10 degrees along the edge from vertex 0 to vertex 1
+        let mut mesh = Mesh::new();
+        let fold = 10f32.to_radians();
+        mesh.vertices = vec![
+            Vec4::new(0.0, 0.0, 0.0, 1.0),
+            Vec4::new(1.0, 0.0, 0.0, 1.0),
+            Vec4::new(0.5, 1.0, 0.0, 1.0),
+            Vec4::new(0.5, -fold.cos(), fold.sin(), 1.0),
+        ];
+        mesh.faces = vec![face(&[0, 1, 2]), face(&[1, 0, 3])];
+
+        mesh.generate_normals(NormalWeighting::Area, Some(30f32.to_radians()));
+        assert_eq!(mesh.faces[0][0].normal_index, mesh.faces[1][1].normal_index);
+        assert_eq!(mesh.vertex_normals.len(), 4);
+
+        mesh.generate_normals(NormalWeighting::Area, Some(5f32.to_radians()));
+        assert_ne!(mesh.faces[0][0].normal_index, mesh.faces[1][1].normal_index);
+        assert_eq!(mesh.vertex_normals.len(), 6);
+    }
+
+    #[test]
+    fn smoothing_everywhere_matches_the_pairwise_pass() {
+        // a cone whose tip is shared by many faces
+        let mut mesh = Mesh::new();
+        let sides = 64;
+        mesh.vertices.push(Vec4::new(0.0, 1.0, 0.0, 1.0));
+        for i in 0..sides {
+            let angle = i as f32 / sides as f32 * std::f32::consts::TAU;
+            mesh.vertices
+                .push(Vec4::new(angle.cos(), 0.0, -angle.sin(), 1.0));
+        }
+        for i in 0..sides {
+            mesh.faces.push(face(&[0, i + 1, (i + 1) % sides + 1]));
+        }
+
+        for weighting in [NormalWeighting::Area, NormalWeighting::Angle] {
+            let mut smooth = mesh.clone();
+            smooth.generate_normals(weighting, None);
+            // a crease angle that never creases takes the pairwise pass
+            let mut pairwise = mesh.clone();
+            pairwise.generate_normals(weighting, Some(std::f32::consts::PI));
+
+            assert_eq!(smooth.vertex_normals.len(), sides as usize + 1);
+            assert_close(normal_at_vertex(&smooth, 0), Vec3::new(0.0, 1.0, 0.0));
+            for (f, face) in smooth.faces.iter().enumerate() {
+                for corner in 0..face.len() {
+                    assert_close(
+                        normal_at(&smooth, f, corner),
+                        normal_at(&pairwise, f, corner),
+                    );
+                }
+            }
+        }
+    }
+}
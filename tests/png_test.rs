@@ -0,0 +1,225 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::graphics::{
+        RenderTarget, color, image_for_path,
+        png::{ColorType, Compression, Image, adler32, crc32, zlib_compress},
+        pnm,
+    };
+
+    /// Reads `bits` bits least significant bit first.
+    struct BitReader<'a> {
+        data: &'a [u8],
+        position: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, bits: u32) -> u32 {
+            let mut value = 0;
+            for i in 0..bits {
+                let bit = (self.data[self.position / 8] >> (self.position % 8)) & 1;
+                value |= (bit as u32) << i;
+                self.position += 1;
+            }
+            value
+        }
+
+        /// Huffman codes come most significant bit first.
+        fn code(&mut self, bits: u32) -> u32 {
+            (0..bits).fold(0, |code, _| (code << 1) | self.bits(1))
+        }
+
+        fn fixed_literal(&mut self) -> u32 {
+            let code = self.code(7);
+            if code <= 0x17 {
+                return code + 256;
+            }
+            let code = (code << 1) | self.bits(1);
+            if (0x30..=0xbf).contains(&code) {
+                return code - 0x30;
+            }
+            if (0xc0..=0xc7).contains(&code) {
+                return code - 0xc0 + 280;
+            }
+            ((code << 1) | self.bits(1)) - 0x190 + 144
+        }
+    }
+
+    /// Just enough of inflate to read back stored and fixed Huffman blocks.
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        const LENGTH_BASE: [u32; 29] = [
+            3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99,
+            115, 131, 163, 195, 227, 258,
+        ];
+        const LENGTH_EXTRA: [u32; 29] = [
+            0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+        ];
+
+        let mut reader = BitReader { data, position: 0 };
+        let mut out: Vec<u8> = Vec::new();
+
+        loop {
+            let is_final = reader.bits(1) == 1;
+            match reader.bits(2) {
+                0 => {
+                    reader.position = reader.position.div_ceil(8) * 8;
+                    let start = reader.position / 8;
+                    let length = u16::from_le_bytes([data[start], data[start + 1]]) as usize;
+                    let complement = u16::from_le_bytes([data[start + 2], data[start + 3]]);
+                    assert_eq!(!(length as u16), complement);
+                    out.extend_from_slice(&data[start + 4..start + 4 + length]);
+                    reader.position = (start + 4 + length) * 8;
+                }
+                1 => loop {
+                    let symbol = reader.fixed_literal();
+                    if symbol < 256 {
+                        out.push(symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        break;
+                    }
+
+                    let i = (symbol - 257) as usize;
+                    let length = LENGTH_BASE[i] + reader.bits(LENGTH_EXTRA[i]);
+
+                    let code = reader.code(5);
+                    let extra = if code < 4 { 0 } else { code / 2 - 1 };
+                    let base = if code < 4 {
+                        code + 1
+                    } else {
+                        ((2 + (code & 1)) << extra) + 1
+                    };
+                    let distance = (base + reader.bits(extra)) as usize;
+
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                },
+                block_type => panic!("unexpected block type {}", block_type),
+            }
+
+            if is_final {
+                return out;
+            }
+        }
+    }
+
+    fn unzlib(data: &[u8]) -> Vec<u8> {
+        assert_eq!((data[0] as u16 * 256 + data[1] as u16) % 31, 0);
+        let out = inflate(&data[2..data.len() - 4]);
+        let checksum = u32::from_be_bytes(data[data.len() - 4..].try_into().unwrap());
+        assert_eq!(adler32(&out), checksum);
+        out
+    }
+
+    fn sample_data() -> Vec<u8> {
+        // repeats at short and long distances, plus a stretch with no matches
+        let mut data = b"tiny renderer tiny renderer tiny tiny tiny!".repeat(50);
+        data.extend((0..70000u64).map(|i| (i * i % 251) as u8));
+        data.extend(std::iter::repeat_n(0, 1000));
+        data
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(b"IEND"), 0xae426082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn zlib_round_trips() {
+        let data = sample_data();
+
+        for compression in [Compression::Stored, Compression::Deflate] {
+            assert_eq!(unzlib(&zlib_compress(&data, compression)), data);
+            assert_eq!(unzlib(&zlib_compress(&[], compression)), Vec::<u8>::new());
+        }
+
+        assert!(zlib_compress(&data, Compression::Deflate).len() < data.len() / 2);
+    }
+
+    #[test]
+    fn writes_png_chunks_top_row_first() {
+        let mut image = Image::new("unused.png", 2, 2, ColorType::RGB);
+        image.set(0, 1, &color::RED);
+        image.set(1, 0, &color::BLUE);
+
+        let bytes = image.to_bytes();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        // IHDR: 2x2, 8 bit RGB
+        assert_eq!(&bytes[8..16], &[0, 0, 0, 13, b'I', b'H', b'D', b'R']);
+        assert_eq!(&bytes[16..29], &[0, 0, 0, 2, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+        assert_eq!(
+            u32::from_be_bytes(bytes[29..33].try_into().unwrap()),
+            crc32(&bytes[12..29])
+        );
+
+        assert_eq!(&bytes[bytes.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+
+        let idat_length = u32::from_be_bytes(bytes[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&bytes[37..41], b"IDAT");
+        let rows = unzlib(&bytes[41..41 + idat_length]);
+
+        // undo the filters to get the pixels back
+        let mut pixels: Vec<Vec<u8>> = Vec::new();
+        for row in rows.chunks(7) {
+            let (filter, filtered) = (row[0], &row[1..]);
+            let previous = pixels.last().cloned().unwrap_or(vec![0; 6]);
+            let mut pixel_row = vec![0u8; 6];
+            for i in 0..6 {
+                let left = if i >= 3 { pixel_row[i - 3] } else { 0 };
+                let up_left = if i >= 3 { previous[i - 3] } else { 0 };
+                let predictor = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => previous[i],
+                    3 => ((left as u16 + previous[i] as u16) / 2) as u8,
+                    _ => {
+                        let p = left as i16 + previous[i] as i16 - up_left as i16;
+                        let (pa, pb, pc) = (
+                            (p - left as i16).abs(),
+                            (p - previous[i] as i16).abs(),
+                            (p - up_left as i16).abs(),
+                        );
+                        if pa <= pb && pa <= pc {
+                            left
+                        } else if pb <= pc {
+                            previous[i]
+                        } else {
+                            up_left
+                        }
+                    }
+                };
+                pixel_row[i] = filtered[i].wrapping_add(predictor);
+            }
+            pixels.push(pixel_row);
+        }
+
+        assert_eq!(pixels, vec![vec![255, 0, 0, 0, 0, 0], vec![0, 0, 0, 0, 0, 255]]);
+    }
+
+    #[test]
+    fn writes_binary_ppm_and_pgm() {
+        let mut ppm = pnm::Image::new("unused.ppm", 2, 1, pnm::Format::Ppm);
+        ppm.set(1, 0, &color::PURPLE);
+        assert_eq!(ppm.to_bytes(), b"P6\n2 1\n255\n\0\0\0\xae\x32\xdc");
+
+        let mut pgm = pnm::Image::new("unused.pgm", 1, 2, pnm::Format::Pgm);
+        pgm.set(0, 0, &color::GREEN);
+        assert_eq!(pgm.to_bytes(), b"P5\n1 2\n255\n\0\x96");
+    }
+
+    #[test]
+    fn picks_the_encoder_from_the_extension() {
+        for path in ["out.tga", "out.PNG", "out.ppm", "out.pgm"] {
+            let image = image_for_path(path, 4, 3).unwrap();
+            assert_eq!((image.width(), image.height()), (4, 3));
+        }
+
+        assert!(image_for_path("out.bmp", 4, 3).is_err());
+        assert!(image_for_path("out", 4, 3).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::rasterize_triangle_scanline,
+        geometry::{Mat3, Mat4, Vec3},
+        graphics::{DepthBuffer, RenderTarget, color},
+        lighting::Material,
+        shader::{ColorShader, ScreenVertex, Uniforms, Varyings},
+    };
+
+    const SIZE: usize = 40;
+
+    /// Counts how many times each pixel is written.
+    struct CountingTarget {
+        counts: Vec<u32>,
+    }
+
+    impl RenderTarget for CountingTarget {
+        fn width(&self) -> usize {
+            SIZE
+        }
+
+        fn height(&self) -> usize {
+            SIZE
+        }
+
+        fn set(&mut self, x: i32, y: i32, _color: &color::RGBA) {
+            assert!(x >= 0 && y >= 0 && (x as usize) < SIZE && (y as usize) < SIZE);
+            self.counts[x as usize + y as usize * SIZE] += 1;
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    fn vertex(x: f32, y: f32) -> ScreenVertex {
+        ScreenVertex {
+            x,
+            y,
+            z: 0.5,
+            inv_w: 1.0,
+            varyings: Varyings::new(),
+        }
+    }
+
+    /// Rasterizes each triangle with its own depth buffer, so a pixel written twice is counted
+    /// twice rather than rejected by the depth test.
+    fn rasterize(triangles: &[[(f32, f32); 3]]) -> CountingTarget {
+        let uniforms = Uniforms {
+            model: Mat4::identity(),
+            view: Mat4::identity(),
+            projection: Mat4::identity(),
+            model_view_projection: Mat4::identity(),
+            normal_matrix: Mat3::identity(),
+            camera_position: Vec3::new(0.0, 0.0, 0.0),
+            color: color::GREEN,
+            diffuse_texture: None,
+            lights: &[],
+            ambient_light: color::RGBA {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            material: Material::default(),
+        };
+
+        let mut target = CountingTarget {
+            counts: vec![0; SIZE * SIZE],
+        };
+        for [a, b, c] in triangles {
+            let mut depth_buffer = DepthBuffer::new(SIZE, SIZE);
+            rasterize_triangle_scanline(
+                &vertex(a.0, a.1),
+                &vertex(b.0, b.1),
+                &vertex(c.0, c.1),
+                &ColorShader,
+                &uniforms,
+                &mut depth_buffer,
+                &mut target,
+            );
+        }
+        target
+    }
+
+    #[test]
+    fn fan_of_shared_edges_writes_every_pixel_once() {
+        // centered on a pixel center, with spokes through pixel centers and at odd angles, and
+        // alternating winding
+        let center = (20.5, 20.5);
+        let ring: Vec<(f32, f32)> = (0..16)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / 16.0;
+                let radius = if i % 4 == 0 { 16.0 } else { 14.3 };
+                (
+                    center.0 + radius * angle.cos(),
+                    center.1 + radius * angle.sin(),
+                )
+            })
+            .collect();
+
+        let triangles: Vec<[(f32, f32); 3]> = (0..ring.len())
+            .map(|i| {
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                if i % 2 == 0 {
+                    [center, a, b]
+                } else {
+                    [b, center, a]
+                }
+            })
+            .collect();
+
+        let target = rasterize(&triangles);
+
+        assert!(target.counts.iter().all(|&count| count <= 1));
+        // everything around the center, well inside the ring, is covered
+        for y in 10..31 {
+            for x in 10..31 {
+                assert_eq!(target.counts[x + y * SIZE], 1, "pixel {}, {}", x, y);
+            }
+        }
+        assert_eq!(target.counts[0], 0);
+    }
+
+    #[test]
+    fn grid_of_triangles_tiles_the_target_exactly() {
+        // cells split along diagonals that pass through pixel centers, with every edge shared
+        let mut triangles = Vec::new();
+        for row in 0..8 {
+            for col in 0..8 {
+                let (x0, y0) = (col as f32 * 5.0, row as f32 * 5.0);
+                let (x1, y1) = (x0 + 5.0, y0 + 5.0);
+
+                if (row + col) % 2 == 0 {
+                    triangles.push([(x0, y0), (x1, y0), (x1, y1)]);
+                    triangles.push([(x0, y0), (x1, y1), (x0, y1)]);
+                } else {
+                    triangles.push([(x0, y0), (x1, y0), (x0, y1)]);
+                    triangles.push([(x1, y0), (x1, y1), (x0, y1)]);
+                }
+            }
+        }
+
+        let target = rasterize(&triangles);
+        assert!(target.counts.iter().all(|&count| count == 1));
+    }
+
+    #[test]
+    fn skips_degenerate_and_subpixel_triangles() {
+        let target = rasterize(&[
+            [(5.0, 5.0), (10.0, 10.0), (15.0, 15.0)],
+            [(3.1, 3.1), (3.3, 3.1), (3.2, 3.3)],
+        ]);
+        assert!(target.counts.iter().all(|&count| count == 0));
+    }
+}
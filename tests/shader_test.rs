@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec4,
+        graphics::{RenderTarget, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{DrawType, TinyRendererBuilder},
+        shader::{Shader, Uniforms, Varyings, VertexInput},
+    };
+
+    const SIZE: usize = 100;
+
+    struct MemoryTarget {
+        pixels: Vec<Option<color::RGBA>>,
+    }
+
+    impl RenderTarget for MemoryTarget {
+        fn width(&self) -> usize {
+            SIZE
+        }
+
+        fn height(&self) -> usize {
+            SIZE
+        }
+
+        fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+            self.pixels[x as usize + y as usize * SIZE] = Some(*color);
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    /// Shades red from left to right and discards the leftmost quarter.
+    struct GradientShader;
+
+    impl Shader for GradientShader {
+        fn vertex(
+            &self,
+            input: &VertexInput,
+            uniforms: &Uniforms,
+            varyings: &mut Varyings,
+        ) -> Vec4<f32> {
+            varyings.push((input.position.x + 1.0) / 2.0);
+            &uniforms.model_view_projection * &input.position
+        }
+
+        fn fragment(&self, varyings: &Varyings, _: &Uniforms) -> Option<color::RGBA> {
+            let t = varyings.get(0);
+            if t < 0.25 {
+                return None;
+            }
+
+            Some(color::RGBA {
+                r: (t * 255.0) as u8,
+                g: 0,
+                b: 0,
+                a: 255,
+            })
+        }
+    }
+
+    fn full_screen_quad() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, 1.0, 0.0, 1.0),
+            Vec4::new(-1.0, 1.0, 0.0, 1.0),
+        ];
+
+        let face = |indices: [i32; 3]| {
+            indices
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face([0, 1, 2]), face([0, 2, 3])];
+        mesh
+    }
+
+    #[test]
+    fn varyings_are_interpolated_and_fragments_discarded() {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(MemoryTarget {
+                pixels: vec![None; SIZE * SIZE],
+            })
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build();
+
+        let id = renderer.add_mesh(full_screen_quad());
+        renderer.set_draw_type(id, DrawType::Fill);
+        renderer.set_shader(id, GradientShader);
+        renderer.draw().unwrap();
+
+        let pixel = |x: usize, y: usize| renderer.render_output().pixels[x + y * SIZE];
+
+        // discarded fragments leave both the color and the depth untouched
+        assert_eq!(pixel(10, 50), None);
+        assert_eq!(renderer.depth_buffer().get(10, 50), Some(f32::NEG_INFINITY));
+
+        // varyings are sampled at the pixel center, x = 50.5 of 100
+        assert_eq!(pixel(50, 50).unwrap().r, 128);
+        assert!(pixel(25, 50).unwrap().r < pixel(75, 50).unwrap().r);
+        assert!(renderer.depth_buffer().get(75, 50).unwrap().is_finite());
+    }
+}
@@ -0,0 +1,167 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::rc::Rc;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::{Vec3, Vec4},
+        graphics::{
+            Filter, RenderTarget, Texture, TextureError, WrapMode, color,
+            tga::{self, ColorType, ImageType},
+        },
+        mesh::{FaceElement, Mesh},
+        renderer::TinyRendererBuilder,
+        shader::TextureShader,
+    };
+
+    const SIZE: usize = 64;
+
+    const BLACK: color::RGBA = color::RGBA {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    const WHITE: color::RGBA = color::RGBA {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+
+    struct MemoryTarget {
+        pixels: Vec<Option<color::RGBA>>,
+    }
+
+    impl RenderTarget for MemoryTarget {
+        fn width(&self) -> usize {
+            SIZE
+        }
+
+        fn height(&self) -> usize {
+            SIZE
+        }
+
+        fn set(&mut self, x: i32, y: i32, color: &color::RGBA) {
+            self.pixels[x as usize + y as usize * SIZE] = Some(*color);
+        }
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    // black in the bottom left and top right, white elsewhere
+    fn checker() -> Texture {
+        Texture::new(2, 2, vec![BLACK, WHITE, WHITE, BLACK])
+    }
+
+    #[test]
+    fn nearest_sampling_and_wrap_modes() {
+        let texture = checker().with_filter(Filter::Nearest);
+        assert_eq!(texture.sample(0.25, 0.25), BLACK);
+        assert_eq!(texture.sample(0.75, 0.25), WHITE);
+        assert_eq!(texture.sample(1.25, 0.25), BLACK);
+
+        let clamped = texture.clone().with_wrap_mode(WrapMode::Clamp);
+        assert_eq!(clamped.sample(1.25, 0.25), WHITE);
+        assert_eq!(clamped.sample(-3.0, 0.25), BLACK);
+
+        let mirrored = texture.with_wrap_mode(WrapMode::Mirror);
+        assert_eq!(mirrored.sample(1.25, 0.25), WHITE);
+        assert_eq!(mirrored.sample(-0.25, 0.25), BLACK);
+    }
+
+    #[test]
+    fn bilinear_sampling_blends_neighbours() {
+        let texture = checker().with_wrap_mode(WrapMode::Clamp);
+
+        assert_eq!(texture.sample(0.25, 0.25), BLACK);
+        assert_eq!(texture.sample(0.5, 0.25).r, 128);
+        assert_eq!(texture.sample(0.5, 0.5).r, 128);
+    }
+
+    #[test]
+    fn texture_loads_from_tga() {
+        let path = std::env::temp_dir().join("tiny_renderer_texture_test.tga");
+        let path = path.to_str().unwrap();
+
+        let mut image = tga::Image::new(
+            path,
+            3,
+            2,
+            ImageType::UncompressedTrueColor,
+            ColorType::RGBA,
+        );
+        image.set(2, 1, &color::PURPLE);
+        image.render().unwrap();
+
+        let texture = Texture::from_tga_file(path).unwrap();
+        assert_eq!((texture.width(), texture.height()), (3, 2));
+        assert_eq!(texture.texel(2, 1), color::PURPLE);
+        assert_eq!(texture.texel(0, 0).a, 0);
+    }
+
+    #[test]
+    fn empty_tga_files_are_an_error() {
+        let path = std::env::temp_dir().join("tiny_renderer_empty_texture_test.tga");
+        let path = path.to_str().unwrap();
+
+        let mut image =
+            tga::Image::new(path, 0, 0, ImageType::UncompressedTrueColor, ColorType::RGB);
+        image.render().unwrap();
+
+        assert!(matches!(
+            Texture::from_tga_file(path),
+            Err(TextureError::Empty)
+        ));
+    }
+
+    #[test]
+    fn textured_quad_uses_texture_coordinates() {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, 1.0, 0.0, 1.0),
+            Vec4::new(-1.0, 1.0, 0.0, 1.0),
+        ];
+        mesh.texture_coordinates = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ];
+
+        let face = |indices: [i32; 3]| {
+            indices
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: Some(i),
+                    normal_index: None,
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face([0, 1, 2]), face([0, 2, 3])];
+
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(MemoryTarget {
+                pixels: vec![None; SIZE * SIZE],
+            })
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build();
+
+        let id = renderer.add_mesh(mesh);
+        renderer.set_shader(id, TextureShader);
+        renderer.set_texture(id, Rc::new(checker().with_filter(Filter::Nearest)));
+        renderer.draw().unwrap();
+
+        let pixel = |x: usize, y: usize| renderer.render_output().pixels[x + y * SIZE];
+        assert_eq!(pixel(10, 10), Some(BLACK));
+        assert_eq!(pixel(50, 10), Some(WHITE));
+        assert_eq!(pixel(10, 50), Some(WHITE));
+        assert_eq!(pixel(50, 50), Some(BLACK));
+    }
+}
@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::graphics::{
+        RenderTarget, color,
+        tga::{ColorType, Image, ImageType, TgaError},
+    };
+
+    fn rgba(r: u8, g: u8, b: u8, a: u8) -> color::RGBA {
+        color::RGBA { r, g, b, a }
+    }
+
+    fn header(image_type: u8, width: u16, height: u16, bpp: u8, descriptor: u8) -> Vec<u8> {
+        let mut bytes = vec![0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        bytes.push(bpp);
+        bytes.push(descriptor);
+        bytes
+    }
+
+    #[test]
+    fn reads_uncompressed_with_id_color_map_and_top_left_origin() {
+        let mut bytes = header(2, 2, 2, 24, 0x20);
+
+        // 3 byte ID field and a 2 entry, 24 bit color map that true color images may carry
+        bytes[0] = 3;
+        bytes[1] = 1;
+        bytes[5..7].copy_from_slice(&2u16.to_le_bytes());
+        bytes[7] = 24;
+        bytes.extend_from_slice(b"abc");
+        bytes.extend_from_slice(&[9; 6]);
+
+        // BGR pixels, top row first
+        bytes.extend_from_slice(&[0, 0, 255, 0, 255, 0]);
+        bytes.extend_from_slice(&[255, 0, 0, 255, 255, 255]);
+
+        let image = Image::from_bytes("unused.tga", &bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(image.id(), b"abc");
+        assert_eq!(image.get(0, 1), Some(rgba(255, 0, 0, 255)));
+        assert_eq!(image.get(1, 1), Some(rgba(0, 255, 0, 255)));
+        assert_eq!(image.get(0, 0), Some(rgba(0, 0, 255, 255)));
+        assert_eq!(image.get(1, 0), Some(rgba(255, 255, 255, 255)));
+    }
+
+    #[test]
+    fn reads_rle_true_color_and_right_to_left_origin() {
+        let mut bytes = header(10, 3, 1, 32, 0x18);
+
+        // a run of two BGRA pixels followed by one raw pixel, stored right to left
+        bytes.extend_from_slice(&[0x81, 1, 2, 3, 4]);
+        bytes.extend_from_slice(&[0x00, 5, 6, 7, 8]);
+
+        let image = Image::from_bytes("unused.tga", &bytes).unwrap();
+        assert_eq!(image.get(0, 0), Some(rgba(7, 6, 5, 8)));
+        assert_eq!(image.get(1, 0), Some(rgba(3, 2, 1, 4)));
+        assert_eq!(image.get(2, 0), Some(rgba(3, 2, 1, 4)));
+    }
+
+    #[test]
+    fn reads_rle_grayscale() {
+        let mut bytes = header(11, 4, 1, 8, 0);
+        bytes.extend_from_slice(&[0x01, 10, 20, 0x81, 30]);
+
+        let image = Image::from_bytes("unused.tga", &bytes).unwrap();
+        let values: Vec<u8> = (0..4).map(|x| image.get(x, 0).unwrap().r).collect();
+        assert_eq!(values, vec![10, 20, 30, 30]);
+    }
+
+    #[test]
+    fn rejects_truncated_and_unsupported_files() {
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &[0, 0, 2]),
+            Err(TgaError::Truncated)
+        ));
+
+        let mut missing_pixels = header(2, 2, 2, 24, 0);
+        missing_pixels.extend_from_slice(&[0; 5]);
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &missing_pixels),
+            Err(TgaError::Truncated)
+        ));
+
+        let mut missing_packet = header(10, 2, 2, 24, 0);
+        missing_packet.extend_from_slice(&[0x83, 1, 2]);
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &missing_packet),
+            Err(TgaError::Truncated)
+        ));
+
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &header(1, 1, 1, 8, 0)),
+            Err(TgaError::UnsupportedImageType(1))
+        ));
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &header(2, 1, 1, 16, 0)),
+            Err(TgaError::UnsupportedBitsPerPixel {
+                image_type: 2,
+                bits_per_pixel: 16
+            })
+        ));
+        assert!(matches!(
+            Image::from_file("does/not/exist.tga"),
+            Err(TgaError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn tiny_files_claiming_huge_images_are_truncated() {
+        // one run packet can't fill 65535 x 65535 pixels, and nothing that big is reserved
+        let mut bytes = header(10, u16::MAX, u16::MAX, 32, 0);
+        bytes.extend_from_slice(&[0xff, 1, 2, 3, 4]);
+        assert!(matches!(
+            Image::from_bytes("unused.tga", &bytes),
+            Err(TgaError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn reads_empty_right_to_left_images() {
+        for (width, height) in [(0, 4), (4, 0), (0, 0)] {
+            let bytes = header(2, width, height, 24, 0x10);
+            let image = Image::from_bytes("unused.tga", &bytes).unwrap();
+            assert_eq!(
+                (image.width(), image.height()),
+                (width as usize, height as usize)
+            );
+        }
+    }
+
+    #[test]
+    fn writes_rle_packets_per_row() {
+        let mut image = Image::new(
+            "unused.tga",
+            5,
+            2,
+            ImageType::RleGrayScale,
+            ColorType::GrayScale,
+        );
+        for (x, value) in [7, 7, 7, 1, 2].iter().enumerate() {
+            image.set(x as i32, 0, &rgba(0, 0, 0, *value));
+        }
+
+        let bytes = image.to_bytes();
+        assert_eq!(&bytes[18..], &[0x82, 7, 0x01, 1, 2, 0x84, 0]);
+    }
+
+    #[test]
+    fn writes_empty_rle_images() {
+        for (width, height) in [(0, 4), (4, 0)] {
+            let image = Image::new(
+                "unused.tga",
+                width,
+                height,
+                ImageType::RleTrueColor,
+                ColorType::RGB,
+            );
+
+            let bytes = image.to_bytes();
+            assert_eq!(bytes.len(), 18);
+            let read = Image::from_bytes("unused.tga", &bytes).unwrap();
+            assert_eq!(
+                (read.width(), read.height()),
+                (width as usize, height as usize)
+            );
+        }
+    }
+
+    #[test]
+    fn rle_round_trips_for_every_color_type() {
+        let cases = [
+            (ImageType::RleTrueColor, ColorType::RGB),
+            (ImageType::RleTrueColor, ColorType::RGBA),
+            (ImageType::RleGrayScale, ColorType::GrayScale),
+        ];
+
+        for (image_type, color_type) in cases {
+            let mut image = Image::new("unused.tga", 300, 20, image_type, color_type);
+            for x in 0..300 {
+                // long runs, single pixels and raw stretches longer than a packet
+                let value = if x < 140 { 0 } else { (x * 7 % 251) as u8 };
+                image.set(x, 3, &rgba(value, value / 2, value / 3, value));
+            }
+
+            let bytes = image.to_bytes();
+            let decoded = Image::from_bytes("unused.tga", &bytes).unwrap();
+
+            for y in 0..20 {
+                for x in 0..300 {
+                    assert_eq!(
+                        decoded.get(x, y),
+                        image.get(x, y),
+                        "{:?} at {}, {}",
+                        color_type,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rle_shrinks_mostly_black_images() {
+        let mut image = Image::new(
+            "unused.tga",
+            500,
+            500,
+            ImageType::RleTrueColor,
+            ColorType::RGB,
+        );
+        for i in 0..500 {
+            image.set(i, i, &color::GREEN);
+        }
+
+        let bytes = image.to_bytes();
+        assert!(bytes.len() < 500 * 500 * 3 / 50);
+    }
+
+    #[test]
+    fn rgba_pixels_are_written_in_bgra_order() {
+        let path = std::env::temp_dir().join("tiny_renderer_rgba_layout.tga");
+        let path = path.to_str().unwrap();
+
+        let mut image = Image::new(
+            path,
+            2,
+            1,
+            ImageType::UncompressedTrueColor,
+            ColorType::RGBA,
+        );
+        image.set(1, 0, &rgba(1, 2, 3, 4));
+        image.render().unwrap();
+
+        // the 18 byte header is followed by the pixels, each blue, green, red and then alpha
+        let bytes = std::fs::read(path).unwrap();
+        assert_eq!(bytes.len(), 18 + 2 * 4);
+        assert_eq!(bytes[18..], [0, 0, 0, 0, 3, 2, 1, 4]);
+    }
+}
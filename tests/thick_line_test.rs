@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        algorithms::{
+            Algorithms, LineCap, LineJoin, LineStyle, bresenhams_line_alg, draw_thick_polyline,
+            rasterize_triangle,
+        },
+        geometry::{Rect, Vec4},
+        graphics::{Framebuffer, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{DrawType, TinyRendererBuilder},
+    };
+
+    const SIZE: usize = 40;
+
+    fn stroke(points: &[(f32, f32)], closed: bool, style: LineStyle) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(SIZE, SIZE);
+        draw_thick_polyline(
+            points,
+            closed,
+            &style,
+            &color::GREEN,
+            &Rect::from_size(SIZE, SIZE),
+            &mut framebuffer,
+        );
+        framebuffer
+    }
+
+    fn lit(framebuffer: &Framebuffer, x: i32, y: i32) -> bool {
+        framebuffer.get(x, y) == Some(color::GREEN)
+    }
+
+    fn count_lit(framebuffer: &Framebuffer) -> usize {
+        framebuffer
+            .pixels()
+            .iter()
+            .filter(|pixel| **pixel == color::GREEN)
+            .count()
+    }
+
+    #[test]
+    fn caps_finish_the_ends() {
+        let segment = [(10.0, 20.0), (30.0, 20.0)];
+
+        // 4 wide around y = 20 covers rows 18 to 21
+        let butt = stroke(
+            &segment,
+            false,
+            LineStyle::new(4.0, LineCap::Butt, LineJoin::Bevel),
+        );
+        assert_eq!(count_lit(&butt), 20 * 4);
+        assert!(lit(&butt, 10, 18) && lit(&butt, 29, 21));
+        assert!(!lit(&butt, 9, 20) && !lit(&butt, 30, 20) && !lit(&butt, 15, 22));
+
+        let square = stroke(
+            &segment,
+            false,
+            LineStyle::new(4.0, LineCap::Square, LineJoin::Bevel),
+        );
+        assert_eq!(count_lit(&square), 24 * 4);
+        assert!(lit(&square, 8, 18) && lit(&square, 31, 21));
+
+        let round = stroke(
+            &segment,
+            false,
+            LineStyle::new(4.0, LineCap::Round, LineJoin::Bevel),
+        );
+        assert!(lit(&round, 8, 19) && lit(&round, 31, 20));
+        // the corners of the square cap are outside the disc
+        assert!(!lit(&round, 8, 18) && !lit(&round, 31, 21));
+    }
+
+    #[test]
+    fn miter_joins_fill_the_outer_corner() {
+        let corner = [(10.0, 10.0), (30.0, 10.0), (30.0, 30.0)];
+
+        let miter = stroke(
+            &corner,
+            false,
+            LineStyle::new(6.0, LineCap::Butt, LineJoin::Miter),
+        );
+        let bevel = stroke(
+            &corner,
+            false,
+            LineStyle::new(6.0, LineCap::Butt, LineJoin::Bevel),
+        );
+
+        // the outer corner of the turn is at (33, 7)
+        assert!(lit(&miter, 32, 7));
+        assert!(!lit(&bevel, 32, 7));
+        assert!(lit(&bevel, 31, 8));
+        assert!(count_lit(&miter) > count_lit(&bevel));
+    }
+
+    #[test]
+    fn sharp_miters_fall_back_to_bevels() {
+        // a very sharp turn, whose miter would reach far past the limit
+        let spike = [(5.0, 20.0), (35.0, 21.0), (5.0, 22.0)];
+
+        let miter = stroke(
+            &spike,
+            false,
+            LineStyle::new(4.0, LineCap::Butt, LineJoin::Miter),
+        );
+        let bevel = stroke(
+            &spike,
+            false,
+            LineStyle::new(4.0, LineCap::Butt, LineJoin::Bevel),
+        );
+        assert_eq!(count_lit(&miter), count_lit(&bevel));
+    }
+
+    #[test]
+    fn wireframe_edges_use_the_mesh_line_style() {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-0.5, -0.5, 0.0, 1.0),
+            Vec4::new(0.5, -0.5, 0.0, 1.0),
+            Vec4::new(0.0, 0.5, 0.0, 1.0),
+        ];
+        mesh.faces = vec![
+            (0..3)
+                .map(|i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect(),
+        ];
+
+        let draw = |line_style: Option<LineStyle>| {
+            let mut renderer = TinyRendererBuilder::new()
+                .with_render_output(Framebuffer::new(SIZE, SIZE))
+                .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+                .with_color(color::GREEN)
+                .build();
+
+            let id = renderer.add_mesh(mesh.clone());
+            renderer.set_draw_type(id, DrawType::Line);
+            if let Some(line_style) = line_style {
+                renderer.set_line_style(id, line_style);
+            }
+            renderer.draw().unwrap();
+            count_lit(renderer.render_output())
+        };
+
+        let thin = draw(None);
+        let thick = draw(Some(LineStyle::new(3.0, LineCap::Butt, LineJoin::Miter)));
+        assert!(thin > 0);
+        assert!(thick > thin * 2);
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use tiny_renderer::{
+        algorithms::{
+            Algorithms, LineCap, LineJoin, LineStyle, bresenhams_line_alg, rasterize_triangle,
+            rasterize_triangle_scanline, xiaolin_wu_line_alg,
+        },
+        geometry::{Rect, Vec3, Vec4},
+        graphics::{Framebuffer, color},
+        mesh::Mesh,
+        renderer::{Camera, CullMode, DrawType, TinyRenderer, TinyRendererBuilder},
+        shader::{ColorShader, NormalShader, Shader, Uniforms, Varyings, VertexInput},
+    };
+
+    const HEAD_OBJ: &str = "tests/obj/head.obj";
+    const BODY_OBJ: &str = "tests/obj/body.obj";
+
+    // a size that isn't a multiple of any tile size used below, so the edge tiles are partial
+    fn scene(algorithms: Algorithms<Framebuffer>) -> TinyRenderer<Framebuffer> {
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(203, 157))
+            .with_algorithms(algorithms)
+            .with_camera(Camera::perspective(
+                Vec3::new(0.3, 0.2, 2.5),
+                Vec3::new(0.0, 0.0, 0.0),
+                std::f32::consts::FRAC_PI_3,
+            ))
+            .with_color(color::RGBA {
+                r: 200,
+                g: 80,
+                b: 40,
+                a: 160,
+            })
+            .build();
+
+        let head = renderer.add_mesh(Mesh::from_obj_file(HEAD_OBJ).unwrap());
+        renderer.set_sync_shader(head, NormalShader);
+        renderer.set_cull_mode(head, CullMode::Back);
+
+        // wireframes overlapping the head and running off screen
+        let body = renderer.add_mesh(Mesh::from_obj_file(BODY_OBJ).unwrap());
+        renderer.set_draw_type(body, DrawType::Line);
+        renderer.set_scale(body, Vec3::new(0.08, 0.08, 0.08));
+        renderer.set_translation(body, Vec3::new(0.3, -1.5, 0.5));
+
+        let thick_body = renderer.add_mesh_instance(body);
+        renderer.set_draw_type(thick_body, DrawType::Line);
+        renderer.set_scale(thick_body, Vec3::new(0.05, 0.05, 0.05));
+        renderer.set_translation(thick_body, Vec3::new(-0.6, -0.8, 0.2));
+        renderer.set_line_style(
+            thick_body,
+            LineStyle::new(4.0, LineCap::Round, LineJoin::Miter),
+        );
+
+        renderer
+    }
+
+    fn assert_tiled_matches(algorithms: fn() -> Algorithms<Framebuffer>) {
+        let mut reference = scene(algorithms());
+        reference.draw().unwrap();
+
+        let painted = reference
+            .render_output()
+            .pixels()
+            .iter()
+            .filter(|p| p.a > 0);
+        assert!(painted.count() > 5000);
+
+        for (tile_size, thread_count) in [(7, 3), (16, 4), (50, 1), (1000, 2)] {
+            let mut tiled = scene(algorithms());
+            tiled.draw_tiled(tile_size, thread_count).unwrap();
+
+            assert!(
+                tiled.render_output().pixels() == reference.render_output().pixels(),
+                "colors differ with {} pixel tiles",
+                tile_size
+            );
+            // compare bits so the cleared depths, which are infinite, count as equal too
+            let bits = |renderer: &TinyRenderer<Framebuffer>| -> Vec<u32> {
+                let depths = renderer.depth_buffer().data();
+                depths.iter().map(|depth| depth.to_bits()).collect()
+            };
+            assert!(
+                bits(&tiled) == bits(&reference),
+                "depths differ with {} pixel tiles",
+                tile_size
+            );
+            assert_eq!(tiled.stats(), reference.stats());
+        }
+    }
+
+    #[test]
+    fn tiles_match_a_single_thread_with_each_rasterizer() {
+        assert_tiled_matches(|| Algorithms::new(bresenhams_line_alg, rasterize_triangle));
+        assert_tiled_matches(|| Algorithms::new(bresenhams_line_alg, rasterize_triangle_scanline));
+    }
+
+    #[test]
+    fn tiles_match_a_single_thread_when_lines_blend() {
+        assert_tiled_matches(|| Algorithms::new(xiaolin_wu_line_alg, rasterize_triangle));
+    }
+
+    #[test]
+    fn tiles_respect_the_scissor_rect() {
+        let draw = |tiled: bool| {
+            let mut renderer = scene(Algorithms::new(bresenhams_line_alg, rasterize_triangle));
+            renderer.set_scissor(Some(Rect::new(40, 30, 150, 120)));
+            if tiled {
+                renderer.draw_tiled(32, 4).unwrap();
+            } else {
+                renderer.draw().unwrap();
+            }
+            renderer.render_output().pixels().to_vec()
+        };
+
+        assert!(draw(true) == draw(false));
+    }
+
+    #[test]
+    fn rejects_empty_tiles_and_no_threads() {
+        let mut renderer = scene(Algorithms::new(bresenhams_line_alg, rasterize_triangle));
+        assert!(renderer.draw_tiled(0, 4).is_err());
+        assert!(renderer.draw_tiled(16, 0).is_err());
+    }
+
+    // counts the pixels it shades, which a shader can only do in a Cell on a single thread
+    struct CountingShader(Cell<usize>);
+
+    impl Shader for CountingShader {
+        fn vertex(
+            &self,
+            input: &VertexInput,
+            uniforms: &Uniforms,
+            varyings: &mut Varyings,
+        ) -> Vec4<f32> {
+            ColorShader.vertex(input, uniforms, varyings)
+        }
+
+        fn fragment(&self, varyings: &Varyings, uniforms: &Uniforms) -> Option<color::RGBA> {
+            self.0.set(self.0.get() + 1);
+            ColorShader.fragment(varyings, uniforms)
+        }
+    }
+
+    struct PanickingShader;
+
+    impl Shader for PanickingShader {
+        fn vertex(
+            &self,
+            input: &VertexInput,
+            uniforms: &Uniforms,
+            varyings: &mut Varyings,
+        ) -> Vec4<f32> {
+            ColorShader.vertex(input, uniforms, varyings)
+        }
+
+        fn fragment(&self, _: &Varyings, _: &Uniforms) -> Option<color::RGBA> {
+            panic!("shader failed");
+        }
+    }
+
+    #[test]
+    fn shaders_that_are_not_sync_are_only_drawn_on_one_thread() {
+        let mut renderer = scene(Algorithms::new(bresenhams_line_alg, rasterize_triangle));
+        renderer.set_shader(0, CountingShader(Cell::new(0)));
+
+        assert!(renderer.draw_tiled(32, 4).is_err());
+        assert!(renderer.render_output().pixels().iter().all(|p| p.a == 0));
+        renderer.draw().unwrap();
+        assert!(renderer.render_output().pixels().iter().any(|p| p.a > 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "shader failed")]
+    fn shader_panics_reach_the_caller() {
+        let mut renderer = scene(Algorithms::new(bresenhams_line_alg, rasterize_triangle));
+        renderer.set_sync_shader(0, PanickingShader);
+
+        let _ = renderer.draw_tiled(32, 4);
+    }
+}
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::f32::consts::FRAC_PI_2;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::{Vec3, Vec4},
+        graphics::{RenderTarget, color},
+        mesh::{FaceElement, Mesh},
+        renderer::{DrawType, TinyRenderer, TinyRendererBuilder, Transform},
+    };
+
+    const SIZE: usize = 100;
+
+    struct NullTarget;
+
+    impl RenderTarget for NullTarget {
+        fn width(&self) -> usize {
+            SIZE
+        }
+
+        fn height(&self) -> usize {
+            SIZE
+        }
+
+        fn set(&mut self, _x: i32, _y: i32, _color: &color::RGBA) {}
+
+        fn render(&mut self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+    }
+
+    // a thin horizontal bar to the right of the origin
+    fn bar_mesh() -> Mesh {
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(0.0, -0.1, 0.0, 1.0),
+            Vec4::new(0.8, -0.1, 0.0, 1.0),
+            Vec4::new(0.8, 0.1, 0.0, 1.0),
+            Vec4::new(0.0, 0.1, 0.0, 1.0),
+        ];
+
+        let face = |indices: [i32; 3]| {
+            indices
+                .iter()
+                .map(|&i| FaceElement {
+                    vertex_index: Some(i),
+                    texture_index: None,
+                    normal_index: None,
+                })
+                .collect::<Vec<FaceElement>>()
+        };
+        mesh.faces = vec![face([0, 1, 2]), face([0, 2, 3])];
+        mesh
+    }
+
+    fn renderer() -> TinyRenderer<NullTarget> {
+        TinyRendererBuilder::new()
+            .with_render_output(NullTarget)
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .build()
+    }
+
+    fn is_drawn(renderer: &TinyRenderer<NullTarget>, x: i32, y: i32) -> bool {
+        renderer.depth_buffer().get(x, y).unwrap().is_finite()
+    }
+
+    #[test]
+    fn transform_is_applied_without_touching_the_mesh() {
+        let mut renderer = renderer();
+        let id = renderer.add_mesh(bar_mesh());
+        renderer.set_draw_type(id, DrawType::Fill);
+
+        // rotate the bar a quarter turn so it points up, then shift it left
+        renderer.set_rotation(id, Vec3::new(0.0, 0.0, FRAC_PI_2));
+        renderer.set_translation(id, Vec3::new(-0.5, 0.0, 0.0));
+        renderer.draw().unwrap();
+
+        assert!(is_drawn(&renderer, 25, 80));
+        assert!(!is_drawn(&renderer, 80, 50));
+        assert_eq!(renderer.mesh(id).vertices, bar_mesh().vertices);
+        assert_eq!(renderer.rotation(id), Vec3::new(0.0, 0.0, FRAC_PI_2));
+        assert_eq!(renderer.translation(id), Vec3::new(-0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn instances_share_mesh_but_not_transform() {
+        let mut renderer = renderer();
+        let id = renderer.add_mesh(bar_mesh());
+        let instance = renderer.add_mesh_instance(id);
+
+        renderer.set_scale(instance, Vec3::new(1.0, 2.0, 1.0));
+
+        assert!(std::ptr::eq(renderer.mesh(id), renderer.mesh(instance)));
+        assert_eq!(*renderer.transform(id), Transform::new());
+        assert_eq!(renderer.scale(instance), Vec3::new(1.0, 2.0, 1.0));
+    }
+}
@@ -13,11 +13,11 @@ mod tests {
         renderer::{DrawType, TinyRendererBuilder},
     };
 
-    const TRIANGLE: &'static str = "./tests/output/triangle.tga";
-    const TRIANGLE_FILL: &'static str = "./tests/output/triangle_fill.tga";
+    const TRIANGLE: &str = "./tests/output/triangle.tga";
+    const TRIANGLE_FILL: &str = "./tests/output/triangle_fill.tga";
 
-    const TRIANGLE_CREATED_BY_TEST: &'static str = "./tests/output/triangle_created_by_test.tga";
-    const TRIANGLE_FILL_CREATED_BY_TEST: &'static str =
+    const TRIANGLE_CREATED_BY_TEST: &str = "./tests/output/triangle_created_by_test.tga";
+    const TRIANGLE_FILL_CREATED_BY_TEST: &str =
         "./tests/output/triangle_fill_created_by_test.tga";
 
     const WIDTH: u16 = 5000;
@@ -27,8 +27,8 @@ mod tests {
         let mut renderer = TinyRendererBuilder::new()
             .with_render_output(tga::Image::new(
                 TRIANGLE_CREATED_BY_TEST,
-                WIDTH as u16,
-                HEIGHT as u16,
+                WIDTH,
+                HEIGHT,
                 ImageType::UncompressedTrueColor,
                 ColorType::RGB,
             ))
@@ -156,8 +156,8 @@ mod tests {
 
         renderer.set_render_output(tga::Image::new(
             TRIANGLE_FILL_CREATED_BY_TEST,
-            WIDTH as u16,
-            HEIGHT as u16,
+            WIDTH,
+            HEIGHT,
             ImageType::UncompressedTrueColor,
             ColorType::RGB,
         ));
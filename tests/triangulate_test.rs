@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tiny_renderer::{
+        algorithms::{Algorithms, bresenhams_line_alg, rasterize_triangle},
+        geometry::Vec4,
+        graphics::{Framebuffer, color},
+        mesh::{FaceElement, Mesh, ObjLoadOptions, ParseMode},
+        renderer::TinyRendererBuilder,
+    };
+
+    fn load(name: &str, obj: &str) -> Mesh {
+        let path = std::env::temp_dir().join(format!("tiny_renderer_{}.obj", name));
+        fs::write(&path, obj).unwrap();
+        let mesh = Mesh::from_obj_file(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        mesh
+    }
+
+    fn vertex_indices(mesh: &Mesh) -> Vec<[i32; 3]> {
+        mesh.faces
+            .iter()
+            .map(|face| {
+                assert_eq!(face.len(), 3);
+                [0, 1, 2].map(|corner| face[corner].vertex_index.unwrap())
+            })
+            .collect()
+    }
+
+    // twice the signed area of a triangle in the xy plane
+    fn signed_area(mesh: &Mesh, [a, b, c]: [i32; 3]) -> f32 {
+        let (a, b, c) = (
+            mesh.vertices[a as usize],
+            mesh.vertices[b as usize],
+            mesh.vertices[c as usize],
+        );
+        (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+    }
+
+    #[test]
+    fn quads_are_fanned() {
+        let mesh = load(
+            "quad",
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 1\nf 1/1 2/1 3/2 4/2\n",
+        );
+
+        assert_eq!(vertex_indices(&mesh), vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(mesh.faces[1][1].texture_index, Some(1));
+    }
+
+    #[test]
+    fn concave_faces_are_ear_clipped() {
+        // an L shape, whose fan around the first corner would cover the notch
+        let mesh = load(
+            "concave",
+            "v 0 0 0\nv 2 0 0\nv 2 1 0\nv 1 1 0\nv 1 2 0\nv 0 2 0\nf 3 4 5 6 1 2\n",
+        );
+
+        let triangles = vertex_indices(&mesh);
+        assert_eq!(triangles.len(), 4);
+
+        let mut area = 0.0;
+        for triangle in triangles {
+            let triangle_area = signed_area(&mesh, triangle);
+            assert!(
+                triangle_area > 0.0,
+                "{:?} is not counter clockwise",
+                triangle
+            );
+            area += triangle_area / 2.0;
+        }
+        assert_eq!(area, 3.0);
+    }
+
+    #[test]
+    fn negative_indices_count_back_from_the_last_line_read() {
+        let mesh = load(
+            "negative",
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf -3//-1 -2//-1 -1//-1\nv 1 1 0\nf 2 -1 3\n",
+        );
+
+        assert_eq!(vertex_indices(&mesh), vec![[0, 1, 2], [1, 3, 2]]);
+        assert_eq!(mesh.faces[0][2].normal_index, Some(0));
+    }
+
+    #[test]
+    fn faces_of_fewer_than_three_corners_are_dropped() {
+        let path = std::env::temp_dir().join("tiny_renderer_short.obj");
+        fs::write(&path, "v 0 0 0\nv 1 0 0\nf 1 2\nf 1\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        // they are an error unless loading leniently
+        assert!(Mesh::from_obj_file(path).is_err());
+        let options = ObjLoadOptions::new().with_mode(ParseMode::Lenient);
+        let (mesh, warnings) = Mesh::load_obj(path, &options).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert!(mesh.faces.is_empty());
+        assert_eq!(warnings.len(), 2);
+    }
+
+    #[test]
+    fn hand_built_polygons_are_drawn_whole() {
+        let element = |i: i32| FaceElement {
+            vertex_index: Some(i),
+            texture_index: None,
+            normal_index: None,
+        };
+
+        let mut mesh = Mesh::new();
+        mesh.vertices = vec![
+            Vec4::new(-1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, -1.0, 0.0, 1.0),
+            Vec4::new(1.0, 1.0, 0.0, 1.0),
+            Vec4::new(-1.0, 1.0, 0.0, 1.0),
+        ];
+        mesh.faces = vec![
+            (0..4).map(element).collect(),
+            vec![element(0), element(1)],
+            Vec::new(),
+        ];
+
+        let mut renderer = TinyRendererBuilder::new()
+            .with_render_output(Framebuffer::new(20, 20))
+            .with_algorithms(Algorithms::new(bresenhams_line_alg, rasterize_triangle))
+            .with_color(color::RED)
+            .build();
+        renderer.add_mesh(mesh);
+        renderer.draw().unwrap();
+
+        // the quad is drawn as two triangles but counts as one face, and the others have none
+        assert_eq!(renderer.stats().drawn_faces, 1);
+        for pixel in renderer.render_output().pixels() {
+            assert_eq!(pixel.r, color::RED.r);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use tiny_renderer::{
+        algorithms::xiaolin_wu_line_alg,
+        graphics::{Framebuffer, RenderTarget, color},
+    };
+
+    const BLACK: color::RGBA = color::RGBA {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    fn framebuffer() -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(20, 20);
+        framebuffer.clear(&BLACK);
+        framebuffer
+    }
+
+    #[test]
+    fn blends_with_source_over() {
+        let half_red = color::RED.with_coverage(0.5);
+        assert_eq!(half_red.a, 128);
+
+        let blended = half_red.over(&color::BLUE);
+        assert_eq!(
+            blended,
+            color::RGBA {
+                r: 128,
+                g: 0,
+                b: 127,
+                a: 255
+            }
+        );
+
+        // over nothing, the color keeps its own alpha
+        let empty = color::RGBA {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        };
+        assert_eq!(half_red.over(&empty), half_red);
+        assert_eq!(color::GREEN.over(&color::BLUE), color::GREEN);
+
+        let mut framebuffer = framebuffer();
+        framebuffer.set(3, 3, &color::BLUE);
+        framebuffer.blend(3, 3, &half_red);
+        assert_eq!(framebuffer.get(3, 3), Some(blended));
+    }
+
+    #[test]
+    fn axis_aligned_and_diagonal_lines_are_solid() {
+        let mut framebuffer = framebuffer();
+        xiaolin_wu_line_alg(2, 5, 15, 5, &color::GREEN, &mut framebuffer);
+        xiaolin_wu_line_alg(1, 1, 1, 12, &color::GREEN, &mut framebuffer);
+        xiaolin_wu_line_alg(18, 8, 10, 16, &color::GREEN, &mut framebuffer);
+
+        for x in 2..=15 {
+            assert_eq!(framebuffer.get(x, 5), Some(color::GREEN));
+            assert_eq!(framebuffer.get(x, 6), Some(BLACK));
+        }
+        for y in 1..=12 {
+            assert_eq!(framebuffer.get(1, y), Some(color::GREEN));
+        }
+        for i in 0..=8 {
+            assert_eq!(framebuffer.get(18 - i, 8 + i), Some(color::GREEN));
+        }
+    }
+
+    #[test]
+    fn shallow_lines_split_coverage_between_rows() {
+        let mut framebuffer = framebuffer();
+        xiaolin_wu_line_alg(0, 0, 8, 2, &color::GREEN, &mut framebuffer);
+
+        // the ideal line is at y = 0.25 at x = 1, and y = 1 exactly at x = 4
+        assert_eq!(framebuffer.get(1, 0).unwrap().g, 191);
+        assert_eq!(framebuffer.get(1, 1).unwrap().g, 64);
+        assert_eq!(framebuffer.get(4, 1), Some(color::GREEN));
+        assert_eq!(framebuffer.get(4, 2), Some(BLACK));
+
+        // every column adds up to one full pixel of green
+        for x in 0..=8 {
+            let total: u32 = (0..20)
+                .map(|y| framebuffer.get(x, y).unwrap().g as u32)
+                .sum();
+            assert!(
+                (254..=256).contains(&total),
+                "column {} sums to {}",
+                x,
+                total
+            );
+        }
+    }
+}